@@ -0,0 +1,33 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use stack::Stack;
+
+/// Alternates bursts of pushes and pops, the pattern where every pop frees a tile
+/// that the next push has to allocate again
+fn churn(stack: &mut Stack<u64>, burst: u64) {
+    for round in 0..16 {
+        for i in 0..burst {
+            stack.push(black_box(round * burst + i));
+        }
+        for _ in 0..burst {
+            black_box(stack.pop());
+        }
+    }
+}
+
+fn push_pop(c: &mut Criterion) {
+    let mut group = c.benchmark_group("push_pop");
+    for burst in [16u64, 256, 4096] {
+        group.bench_with_input(BenchmarkId::new("allocator", burst), &burst, |b, &burst| {
+            let mut stack = Stack::new();
+            b.iter(|| churn(&mut stack, burst));
+        });
+        group.bench_with_input(BenchmarkId::new("pooled", burst), &burst, |b, &burst| {
+            let mut stack = Stack::pooled(burst as usize);
+            b.iter(|| churn(&mut stack, burst));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, push_pop);
+criterion_main!(benches);
@@ -0,0 +1,326 @@
+//! Aggregating Stack Data Structure
+//!
+//! `AggregateStack` stores, in every tile, the aggregate of that element and everything
+//! below it, so the fold of the whole stack is available in O(1) after any push or pop
+//!
+//! The fold is described by a `Monoid`: an associative operation with an identity,
+//! such as sums, minimums, GCDs or hash combines
+//!
+//! `AggregateQueue` pairs two aggregating stacks into a first-in first-out queue with the
+//! same O(1) aggregate (amortized O(1) pop), the usual way to fold over a sliding window
+//!
+//! Example
+//! ```rust
+//! use stack::aggregate::{AggregateStack, Fold};
+//!
+//! let mut my_stack = AggregateStack::new(Fold::new(0, |a: &i32, b: &i32| a + b));
+//! my_stack.push(2);
+//! my_stack.push(5);
+//! assert_eq!(&7, my_stack.aggregate());
+//! my_stack.pop();
+//! assert_eq!(&2, my_stack.aggregate());
+//! ```
+
+use crate::Stack;
+use core::fmt;
+
+/// An associative operation with an identity, folding elements of type T into a Value
+///
+/// `combine` must be associative but need not be commutative: a stack combines its
+/// elements from the bottom to the top, and a queue from the oldest to the newest
+pub trait Monoid<T> {
+    /// The type of the aggregate
+    type Value;
+    /// Returns the aggregate of no elements
+    fn identity(&self) -> Self::Value;
+    /// Returns the aggregate of a single element
+    fn lift(&self, item: &T) -> Self::Value;
+    /// Combines the aggregate of older elements with the aggregate of newer ones
+    fn combine(&self, older: &Self::Value, newer: &Self::Value) -> Self::Value;
+}
+
+/// A `Monoid` built from an identity value and a closure over elements of the same type
+#[derive(Debug, Clone)]
+pub struct Fold<T, F> {
+    identity: T,
+    op: F,
+}
+
+impl<T, F: Fn(&T, &T) -> T> Fold<T, F> {
+    /// Initialize a new fold, `op` must be associative and `identity` neutral for it
+    pub fn new(identity: T, op: F) -> Self {
+        Fold { identity, op }
+    }
+}
+
+impl<T: Clone, F: Fn(&T, &T) -> T> Monoid<T> for Fold<T, F> {
+    type Value = T;
+
+    fn identity(&self) -> T {
+        self.identity.clone()
+    }
+
+    fn lift(&self, item: &T) -> T {
+        item.clone()
+    }
+
+    fn combine(&self, older: &T, newer: &T) -> T {
+        (self.op)(older, newer)
+    }
+}
+
+/// Stack Data Structure with an O(1) aggregate of its elements
+/// It has a stack, whose tiles hold each element with the aggregate at or below it
+/// It has a monoid, used to compute the aggregates
+pub struct AggregateStack<T, M: Monoid<T>> {
+    stack: Stack<(T, M::Value)>,
+    monoid: M,
+    identity: M::Value,
+}
+
+impl<T, M: Monoid<T>> AggregateStack<T, M> {
+    /// Initialize a new empty stack folding its elements with `monoid`
+    pub fn new(monoid: M) -> Self {
+        AggregateStack {
+            stack: Stack::new(),
+            identity: monoid.identity(),
+            monoid,
+        }
+    }
+    /// Get a reference to data in the head of the stack
+    /// Returns None if the stack is empty
+    pub fn peek(&self) -> Option<&T> {
+        self.stack.peek().map(|(value, _)| value)
+    }
+    /// Pops the top off the stack and returns the data it contains
+    /// Returns None if the stack is empty
+    pub fn pop(&mut self) -> Option<T> {
+        self.stack.pop().map(|(value, _)| value)
+    }
+    /// Pushes the data onto the stack, combining it with the aggregate below [O(1) operation]
+    pub fn push(&mut self, data: T) {
+        let lifted = self.monoid.lift(&data);
+        let aggregate = match self.stack.peek() {
+            Some((_, below)) => self.monoid.combine(below, &lifted),
+            None => lifted,
+        };
+        self.stack.push((data, aggregate));
+    }
+    /// Get a reference to the aggregate of every element in the stack [O(1) operation]
+    /// Returns the identity if the stack is empty
+    pub fn aggregate(&self) -> &M::Value {
+        match self.stack.peek() {
+            Some((_, aggregate)) => aggregate,
+            None => &self.identity,
+        }
+    }
+    /// Returns the number of elements in the stack
+    pub fn size(&self) -> usize {
+        self.stack.size
+    }
+    /// Returns true if the stack holds no elements
+    pub fn is_empty(&self) -> bool {
+        self.stack.head.is_none()
+    }
+    /// Returns an iterator over references to the data, from the top of the stack to the bottom
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &T> {
+        self.stack.iter().map(|(value, _)| value)
+    }
+}
+
+impl<T: fmt::Debug, M: Monoid<T>> fmt::Debug for AggregateStack<T, M>
+where
+    M::Value: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AggregateStack")
+            .field("stack", &self.stack)
+            .finish()
+    }
+}
+
+/// Combines in the opposite order, for a stack whose top holds the oldest element
+#[derive(Clone)]
+struct Reversed<M>(M);
+
+impl<T, M: Monoid<T>> Monoid<T> for Reversed<M> {
+    type Value = M::Value;
+
+    fn identity(&self) -> M::Value {
+        self.0.identity()
+    }
+
+    fn lift(&self, item: &T) -> M::Value {
+        self.0.lift(item)
+    }
+
+    fn combine(&self, older: &M::Value, newer: &M::Value) -> M::Value {
+        self.0.combine(newer, older)
+    }
+}
+
+/// Queue Data Structure with an O(1) aggregate of its elements, built from two stacks
+/// It has a front stack, holding the oldest elements with the oldest on top
+/// It has a back stack, receiving new elements
+///
+/// Example
+/// ```rust
+/// use stack::aggregate::{AggregateQueue, Fold};
+///
+/// // maximum over a sliding window of 3
+/// let mut window = AggregateQueue::new(Fold::new(i32::MIN, |a: &i32, b: &i32| *a.max(b)));
+/// let mut maxima = Vec::new();
+/// for value in vec![1, 3, 2, 0, -1, 4] {
+///     window.push(value);
+///     if window.len() > 3 {
+///         window.pop();
+///     }
+///     maxima.push(window.aggregate());
+/// }
+/// assert_eq!(vec![1, 3, 3, 3, 2, 4], maxima);
+/// ```
+pub struct AggregateQueue<T, M: Monoid<T>> {
+    front: AggregateStack<T, Reversed<M>>,
+    back: AggregateStack<T, M>,
+}
+
+impl<T, M: Monoid<T> + Clone> AggregateQueue<T, M> {
+    /// Initialize a new empty queue folding its elements with `monoid`
+    pub fn new(monoid: M) -> Self {
+        AggregateQueue {
+            front: AggregateStack::new(Reversed(monoid.clone())),
+            back: AggregateStack::new(monoid),
+        }
+    }
+}
+
+impl<T, M: Monoid<T>> AggregateQueue<T, M> {
+    /// Adds the data at the back of the queue [O(1) operation]
+    pub fn push(&mut self, data: T) {
+        self.back.push(data);
+    }
+    /// Removes the oldest data from the queue and returns it [amortized O(1) operation]
+    /// Returns None if the queue is empty
+    pub fn pop(&mut self) -> Option<T> {
+        if self.front.is_empty() {
+            while let Some(data) = self.back.pop() {
+                self.front.push(data);
+            }
+        }
+        self.front.pop()
+    }
+    /// Returns the aggregate of every element in the queue, from the oldest to the newest [O(1) operation]
+    pub fn aggregate(&self) -> M::Value {
+        self.back
+            .monoid
+            .combine(self.front.aggregate(), self.back.aggregate())
+    }
+    /// Returns the number of elements in the queue
+    pub fn len(&self) -> usize {
+        self.front.size() + self.back.size()
+    }
+    /// Returns true if the queue holds no elements
+    pub fn is_empty(&self) -> bool {
+        self.front.is_empty() && self.back.is_empty()
+    }
+}
+
+impl<T: fmt::Debug, M: Monoid<T>> fmt::Debug for AggregateQueue<T, M>
+where
+    M::Value: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AggregateQueue")
+            .field("front", &self.front.stack)
+            .field("back", &self.back.stack)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AggregateQueue, AggregateStack, Fold, Monoid};
+
+    fn gcd(a: &u64, b: &u64) -> u64 {
+        let (mut a, mut b) = (*a, *b);
+        while b != 0 {
+            let r = a % b;
+            a = b;
+            b = r;
+        }
+        a
+    }
+
+    #[test]
+    fn stack() {
+        let mut stack = AggregateStack::new(Fold::new(0, gcd));
+        assert_eq!(&0, stack.aggregate());
+        stack.push(12);
+        stack.push(18);
+        assert_eq!(&6, stack.aggregate());
+        stack.push(4);
+        assert_eq!(&2, stack.aggregate());
+        assert_eq!(3, stack.size());
+        assert_eq!(Some(4), stack.pop());
+        assert_eq!(&6, stack.aggregate());
+        assert_eq!(vec![&18, &12], stack.iter().collect::<Vec<_>>());
+    }
+
+    /// Counts the elements and concatenates them, to check the combination order
+    #[derive(Clone)]
+    struct Concat;
+
+    impl Monoid<char> for Concat {
+        type Value = (usize, String);
+
+        fn identity(&self) -> Self::Value {
+            (0, String::new())
+        }
+
+        fn lift(&self, item: &char) -> Self::Value {
+            (1, item.to_string())
+        }
+
+        fn combine(&self, older: &Self::Value, newer: &Self::Value) -> Self::Value {
+            (older.0 + newer.0, format!("{}{}", older.1, newer.1))
+        }
+    }
+
+    #[test]
+    fn non_commutative() {
+        let mut stack = AggregateStack::new(Concat);
+        for c in "abc".chars() {
+            stack.push(c);
+        }
+        assert_eq!(&(3, "abc".to_string()), stack.aggregate());
+
+        let mut queue = AggregateQueue::new(Concat);
+        for c in "abcd".chars() {
+            queue.push(c);
+        }
+        assert_eq!(Some('a'), queue.pop());
+        queue.push('e');
+        assert_eq!((4, "bcde".to_string()), queue.aggregate());
+        assert_eq!(Some('b'), queue.pop());
+        assert_eq!(Some('c'), queue.pop());
+        queue.push('f');
+        assert_eq!((3, "def".to_string()), queue.aggregate());
+    }
+
+    #[test]
+    fn sliding_window_sum() {
+        let values = [5, 1, 4, 2, 8, 3, 7];
+        let mut window = AggregateQueue::new(Fold::new(0, |a: &i32, b: &i32| a + b));
+        for (i, value) in values.iter().enumerate() {
+            window.push(*value);
+            if window.len() > 3 {
+                window.pop();
+            }
+            let start = i.saturating_sub(2);
+            assert_eq!(values[start..=i].iter().sum::<i32>(), window.aggregate());
+        }
+        while window.pop().is_some() {}
+        assert!(window.is_empty());
+        assert_eq!(0, window.aggregate());
+    }
+}
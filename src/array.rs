@@ -0,0 +1,268 @@
+//! Inline Fixed-Capacity Stack Data Structure
+//!
+//! `ArrayStack` stores up to `N` elements inline, so pushing never allocates and the
+//! whole stack can live on the call stack or in a `static`
+//!
+//! Example
+//! ```rust
+//! use stack::{ArrayStack, Full};
+//!
+//! let mut stack = ArrayStack::<i32, 2>::new();
+//! stack.push(1);
+//! stack.push(2);
+//! assert_eq!(Err(Full(3)), stack.try_push(3));
+//! assert_eq!(Some(2), stack.pop());
+//! assert_eq!(Some(1), stack.search(1));
+//! ```
+
+use crate::slice::Slots;
+use crate::Full;
+use core::fmt;
+use core::iter::{FusedIterator, Rev};
+use core::mem::MaybeUninit;
+use core::slice;
+
+/// Stack Data Structure with its storage inline
+/// It has slots, an inline array of N of them holding the data with the top of the stack last
+///
+/// The stack is `Clone` when `T` is, but never `Copy`: it has to drop the elements it holds,
+/// and a type with a destructor cannot be `Copy`
+pub struct ArrayStack<T, const N: usize> {
+    slots: Slots<[MaybeUninit<T>; N]>,
+}
+
+impl<T, const N: usize> ArrayStack<T, N> {
+    /// Initialize a new empty stack holding at most N elements
+    ///
+    /// Example
+    /// ```rust
+    /// # use stack::ArrayStack;
+    /// let new_stack = ArrayStack::<i32, 16>::new();
+    /// ```
+    pub const fn new() -> Self {
+        ArrayStack {
+            // An array of `MaybeUninit` needs no initialization
+            slots: Slots::new(unsafe {
+                MaybeUninit::<[MaybeUninit<T>; N]>::uninit().assume_init()
+            }),
+        }
+    }
+    /// Get a reference to data in the head of the stack
+    /// Returns None if the stack is empty
+    pub fn peek(&self) -> Option<&T> {
+        self.as_slice().last()
+    }
+    /// Get a mutable reference to data in the head of the stack
+    /// Returns None if the stack is empty
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        self.as_mut_slice().last_mut()
+    }
+    /// Pops the top off the stack and returns the data it contains
+    /// Returns None if the stack is empty
+    ///
+    /// Reduces the size of stack by 1 unit
+    pub fn pop(&mut self) -> Option<T> {
+        self.slots.pop()
+    }
+    /// Pushes the data onto the stack
+    ///
+    /// Increases the size of stack by 1 unit
+    ///
+    /// # Panics
+    ///
+    /// Panics if the stack already holds N elements, use `try_push` to get the data back instead
+    pub fn push(&mut self, data: T) {
+        if self.try_push(data).is_err() {
+            panic!("push onto a full ArrayStack of capacity {}", N);
+        }
+    }
+    /// Pushes the data onto the stack
+    /// Hands the data back in `Full` if the stack already holds N elements
+    ///
+    /// Example
+    /// ```rust
+    /// # use stack::{ArrayStack, Full};
+    /// let mut new_stack = ArrayStack::<i32, 1>::new();
+    /// assert_eq!(Ok(()), new_stack.try_push(1));
+    /// assert_eq!(Err(Full(2)), new_stack.try_push(2));
+    /// ```
+    pub fn try_push(&mut self, data: T) -> Result<(), Full<T>> {
+        self.slots.push(data)
+    }
+    /// Returns the number of elements in the stack
+    pub fn size(&self) -> usize {
+        self.slots.size()
+    }
+    /// Returns the maximum number of elements the stack can hold, which is N
+    pub fn capacity(&self) -> usize {
+        N
+    }
+    /// Returns true if a push would be rejected
+    pub fn is_full(&self) -> bool {
+        self.size() == N
+    }
+    /// Returns the number of elements that can be pushed before the stack is full
+    pub fn remaining(&self) -> usize {
+        N - self.size()
+    }
+    /// Removes all the elements from the stack
+    ///
+    /// Resets the size of stack to 0
+    pub fn clear(&mut self) {
+        self.slots.clear();
+    }
+    /// Returns the data as a slice, from the bottom of the stack to the top
+    pub fn as_slice(&self) -> &[T] {
+        self.slots.as_slice()
+    }
+    /// Returns the data as a mutable slice, from the bottom of the stack to the top
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.slots.as_mut_slice()
+    }
+    /// Returns an iterator over references to the data, from the top of the stack to the bottom
+    pub fn iter(&self) -> Rev<slice::Iter<'_, T>> {
+        self.as_slice().iter().rev()
+    }
+    /// Returns an iterator over mutable references to the data, from the top of the stack to the bottom
+    pub fn iter_mut(&mut self) -> Rev<slice::IterMut<'_, T>> {
+        self.as_mut_slice().iter_mut().rev()
+    }
+}
+
+impl<T: PartialEq, const N: usize> ArrayStack<T, N> {
+    /// Searches for data in the whole stack [O(n) operation]
+    /// Returns the position counted from the bottom of the stack, starting at 1
+    /// Returns None if the data is not found
+    pub fn search(&self, data: T) -> Option<usize> {
+        self.as_slice()
+            .iter()
+            .rposition(|value| *value == data)
+            .map(|index| index + 1)
+    }
+}
+
+impl<T, const N: usize> Default for ArrayStack<T, N> {
+    fn default() -> Self {
+        ArrayStack::new()
+    }
+}
+
+/// Clones the elements from the bottom of the stack to the top
+impl<T: Clone, const N: usize> Clone for ArrayStack<T, N> {
+    fn clone(&self) -> Self {
+        let mut stack = ArrayStack::new();
+        for value in self.as_slice() {
+            // Cannot overflow, the clone has the same capacity
+            let _ = stack.try_push(value.clone());
+        }
+        stack
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for ArrayStack<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArrayStack")
+            .field("data", &self.as_slice())
+            .field("size", &self.size())
+            .finish()
+    }
+}
+
+/// Owning iterator over an ArrayStack, created by `ArrayStack::into_iter`
+/// Pops the data from the top of the stack to the bottom
+pub struct IntoIter<T, const N: usize>(ArrayStack<T, N>);
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.size(), Some(self.0.size()))
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> FusedIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> IntoIterator for ArrayStack<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a ArrayStack<T, N> {
+    type Item = &'a T;
+    type IntoIter = Rev<slice::Iter<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut ArrayStack<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = Rev<slice::IterMut<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ArrayStack;
+    use crate::Full;
+    use std::rc::Rc;
+
+    #[test]
+    fn basics() {
+        let mut stack = ArrayStack::<u8, 3>::new();
+        stack.push(3);
+        stack.push(6);
+        stack.push(9);
+        assert_eq!(3, stack.size());
+        assert!(stack.is_full());
+        assert_eq!(Err(Full(12)), stack.try_push(12));
+        assert_eq!(Some(&9), stack.peek());
+        assert_eq!(Some(9), stack.pop());
+        assert_eq!(Some(2), stack.search(6));
+        assert_eq!(None, stack.search(9));
+        *stack.peek_mut().unwrap() += 1;
+        assert_eq!(vec![&7, &3], stack.iter().collect::<Vec<_>>());
+        assert_eq!(vec![7, 3], stack.into_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity 1")]
+    fn push_overflow() {
+        let mut stack = ArrayStack::<u8, 1>::new();
+        stack.push(1);
+        stack.push(2);
+    }
+
+    #[test]
+    fn clone_and_drop() {
+        let counter = Rc::new(());
+        let mut stack = ArrayStack::<Rc<()>, 8>::new();
+        for _ in 0..3 {
+            stack.push(Rc::clone(&counter));
+        }
+        let copy = stack.clone();
+        assert_eq!(3, copy.size());
+        assert_eq!(7, Rc::strong_count(&counter));
+        drop(stack.pop());
+        drop(stack);
+        assert_eq!(4, Rc::strong_count(&counter));
+        let mut into_iter = copy.into_iter();
+        into_iter.next();
+        drop(into_iter);
+        assert_eq!(1, Rc::strong_count(&counter));
+    }
+}
@@ -0,0 +1,322 @@
+//! Async Stack Data Structure, enabled by the `async` feature
+//!
+//! `AsyncStack` is shared between tasks by reference or through an `Arc`, and its `pop`
+//! returns a future that waits until an element is pushed, without tying the crate to
+//! any particular runtime
+//!
+//! Waiting pops are served first come, first served: a push while tasks are waiting hands
+//! its data straight to the one that has waited longest, so a later pop cannot snatch it
+//!
+//! Example
+//! ```rust
+//! use stack::AsyncStack;
+//! use std::sync::Arc;
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let stack = Arc::new(AsyncStack::<i32>::new());
+//! let consumer = {
+//!     let stack = Arc::clone(&stack);
+//!     tokio::spawn(async move { stack.pop().await })
+//! };
+//! stack.push(1).unwrap();
+//! assert_eq!(Some(1), consumer.await.unwrap());
+//! # }
+//! ```
+
+use crate::{Closed, Stack};
+use std::collections::{BTreeMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+
+/// Stack Data Structure whose pops can be awaited
+/// It has a stack, guarded by a mutex along with the waiting pops
+#[derive(Debug)]
+pub struct AsyncStack<T> {
+    inner: Mutex<Inner<T>>,
+}
+
+#[derive(Debug)]
+struct Inner<T> {
+    stack: Stack<T>,
+    // Pops waiting for data, the longest waiting at the front
+    waiters: VecDeque<(u64, Waker)>,
+    // Data handed to a waiting pop that has not been polled since
+    handed: BTreeMap<u64, T>,
+    next_id: u64,
+    closed: bool,
+}
+
+impl<T> Inner<T> {
+    /// Hands the data to the longest waiting pop, or pushes it if nobody is waiting
+    fn deliver(&mut self, data: T) -> Option<Waker> {
+        match self.waiters.pop_front() {
+            Some((id, waker)) => {
+                self.handed.insert(id, data);
+                Some(waker)
+            }
+            None => {
+                self.stack.push(data);
+                None
+            }
+        }
+    }
+}
+
+impl<T> AsyncStack<T> {
+    /// Initialize a new empty open stack
+    pub fn new() -> Self {
+        AsyncStack {
+            inner: Mutex::new(Inner {
+                stack: Stack::new(),
+                waiters: VecDeque::new(),
+                handed: BTreeMap::new(),
+                next_id: 0,
+                closed: false,
+            }),
+        }
+    }
+    /// Pushes the data onto the stack, or hands it to the longest waiting pop
+    /// Hands the data back in `Closed` if the stack was closed
+    pub fn push(&self, data: T) -> Result<(), Closed<T>> {
+        let mut inner = self.lock();
+        if inner.closed {
+            return Err(Closed(data));
+        }
+        let waker = inner.deliver(data);
+        drop(inner);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        Ok(())
+    }
+    /// Returns a future resolving to the top of the stack once there is one
+    ///
+    /// The future resolves to None once the stack is closed and empty
+    ///
+    /// Dropping the future before it resolves never loses data: data already handed
+    /// to it goes to the next waiting pop, or back on top of the stack
+    pub fn pop(&self) -> Pop<'_, T> {
+        Pop {
+            stack: self,
+            id: None,
+        }
+    }
+    /// Pops the top off the stack without waiting
+    /// Returns None if the stack is empty
+    pub fn try_pop(&self) -> Option<T> {
+        self.lock().stack.pop()
+    }
+    /// Returns the number of elements in the stack
+    pub fn size(&self) -> usize {
+        self.lock().stack.size
+    }
+    /// Closes the stack: further pushes are rejected and waiting pops resolve to None,
+    /// while the data already in the stack can still be popped
+    pub fn close(&self) {
+        let mut inner = self.lock();
+        inner.closed = true;
+        let waiters = std::mem::take(&mut inner.waiters);
+        drop(inner);
+        for (_, waker) in waiters {
+            waker.wake();
+        }
+    }
+    /// Returns true if the stack was closed
+    pub fn is_closed(&self) -> bool {
+        self.lock().closed
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner<T>> {
+        self.inner.lock().unwrap()
+    }
+}
+
+impl<T> Default for AsyncStack<T> {
+    fn default() -> Self {
+        AsyncStack::new()
+    }
+}
+
+/// Future returned by `AsyncStack::pop`
+/// It has an id once it waits in the queue of the stack
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct Pop<'a, T> {
+    stack: &'a AsyncStack<T>,
+    id: Option<u64>,
+}
+
+impl<T> Future for Pop<'_, T> {
+    type Output = Option<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut inner = self.stack.lock();
+        let id = match self.id {
+            Some(id) => id,
+            None => {
+                if let Some(data) = inner.stack.pop() {
+                    return Poll::Ready(Some(data));
+                }
+                if inner.closed {
+                    return Poll::Ready(None);
+                }
+                let id = inner.next_id;
+                inner.next_id += 1;
+                inner.waiters.push_back((id, cx.waker().clone()));
+                drop(inner);
+                self.id = Some(id);
+                return Poll::Pending;
+            }
+        };
+        if let Some(data) = inner.handed.remove(&id) {
+            drop(inner);
+            self.id = None;
+            return Poll::Ready(Some(data));
+        }
+        if inner.closed {
+            // A cancelled pop may have put its data back on the stack after the close
+            let data = inner.stack.pop();
+            drop(inner);
+            self.id = None;
+            return Poll::Ready(data);
+        }
+        if let Some((_, waker)) = inner.waiters.iter_mut().find(|(waiter, _)| *waiter == id) {
+            waker.clone_from(cx.waker());
+        }
+        Poll::Pending
+    }
+}
+
+/// A pop dropped while waiting leaves the queue, and passes on any data handed to it
+impl<T> Drop for Pop<'_, T> {
+    fn drop(&mut self) {
+        let id = match self.id {
+            Some(id) => id,
+            None => return,
+        };
+        let mut inner = self.stack.lock();
+        let waker = match inner.handed.remove(&id) {
+            Some(data) => inner.deliver(data),
+            None => {
+                inner.waiters.retain(|(waiter, _)| *waiter != id);
+                None
+            }
+        };
+        drop(inner);
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AsyncStack;
+    use crate::Closed;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Waker};
+    use std::time::Duration;
+
+    fn poll<F: Future + Unpin>(future: &mut F) -> Poll<F::Output> {
+        Pin::new(future).poll(&mut Context::from_waker(Waker::noop()))
+    }
+
+    #[test]
+    fn waiters_are_served_in_order() {
+        let stack = AsyncStack::new();
+        stack.push(1).unwrap();
+        assert_eq!(Poll::Ready(Some(1)), poll(&mut stack.pop()));
+
+        let mut first = stack.pop();
+        let mut second = stack.pop();
+        assert_eq!(Poll::Pending, poll(&mut first));
+        assert_eq!(Poll::Pending, poll(&mut second));
+        stack.push(2).unwrap();
+        // A pop arriving after the push cannot take the data handed to `first`
+        assert_eq!(Poll::Pending, poll(&mut stack.pop()));
+        stack.push(3).unwrap();
+        assert_eq!(Poll::Ready(Some(3)), poll(&mut second));
+        assert_eq!(Poll::Ready(Some(2)), poll(&mut first));
+        assert_eq!(0, stack.size());
+    }
+
+    #[test]
+    fn cancelled_pop_keeps_data() {
+        let stack = AsyncStack::new();
+        let mut cancelled = stack.pop();
+        let mut waiting = stack.pop();
+        assert_eq!(Poll::Pending, poll(&mut cancelled));
+        assert_eq!(Poll::Pending, poll(&mut waiting));
+        stack.push(1).unwrap();
+        drop(cancelled);
+        assert_eq!(Poll::Ready(Some(1)), poll(&mut waiting));
+
+        let mut cancelled = stack.pop();
+        assert_eq!(Poll::Pending, poll(&mut cancelled));
+        stack.push(2).unwrap();
+        drop(cancelled);
+        assert_eq!(Some(2), stack.try_pop());
+    }
+
+    #[test]
+    fn close_wakes_waiters() {
+        let stack = AsyncStack::new();
+        let mut waiting = stack.pop();
+        assert_eq!(Poll::Pending, poll(&mut waiting));
+        stack.close();
+        assert_eq!(Poll::Ready(None), poll(&mut waiting));
+        assert_eq!(Err(Closed(1)), stack.push(1));
+        assert!(stack.is_closed());
+    }
+
+    #[test]
+    fn close_after_cancelled_pop() {
+        let stack = AsyncStack::new();
+        let mut cancelled = stack.pop();
+        let mut waiting = stack.pop();
+        assert_eq!(Poll::Pending, poll(&mut cancelled));
+        assert_eq!(Poll::Pending, poll(&mut waiting));
+        stack.push(1).unwrap();
+        stack.close();
+        drop(cancelled);
+        assert_eq!(Poll::Ready(Some(1)), poll(&mut waiting));
+        assert_eq!(0, stack.size());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn across_tasks() {
+        let stack = Arc::new(AsyncStack::new());
+        let consumers: Vec<_> = (0..8)
+            .map(|_| {
+                let stack = Arc::clone(&stack);
+                tokio::spawn(async move {
+                    let mut sum = 0;
+                    while let Some(value) = stack.pop().await {
+                        sum += value;
+                    }
+                    sum
+                })
+            })
+            .collect();
+        for value in 1..=1000u64 {
+            stack.push(value).unwrap();
+            if value % 100 == 0 {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+        }
+        while stack.size() > 0 {
+            tokio::task::yield_now().await;
+        }
+        stack.close();
+        let mut total = 0;
+        for consumer in consumers {
+            total += consumer.await.unwrap();
+        }
+        assert_eq!(500_500, total);
+    }
+}
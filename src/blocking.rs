@@ -0,0 +1,416 @@
+//! Blocking Stack Data Structure
+//!
+//! `BlockingStack` shares a `Stack` between threads: `pop` waits until another thread
+//! pushes, and with a capacity `push` waits until another thread pops
+//!
+//! `BlockingStack::into_handles` splits it into cloneable `Sender` and `Receiver` handles,
+//! and the stack closes by itself once the last `Sender` is dropped
+//!
+//! Example
+//! ```rust
+//! use stack::BlockingStack;
+//! use std::thread;
+//!
+//! let (sender, receiver) = BlockingStack::<i32>::new().into_handles();
+//! let producer = thread::spawn(move || {
+//!     for i in 0..3 {
+//!         sender.push(i).unwrap();
+//!     }
+//! });
+//! let mut sum = 0;
+//! while let Some(value) = receiver.pop() {
+//!     sum += value;
+//! }
+//! producer.join().unwrap();
+//! assert_eq!(3, sum);
+//! ```
+
+use crate::{Closed, Stack};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::Duration;
+
+/// State shared between threads behind a mutex, with the condition variables
+/// its pushes and pops wait on
+///
+/// `BlockingStack` and `SyncBoundedStack` both wait through it
+#[derive(Debug)]
+pub(crate) struct Waiting<S> {
+    state: Mutex<S>,
+    not_empty: Condvar,
+    not_full: Condvar,
+}
+
+impl<S> Waiting<S> {
+    pub(crate) fn new(state: S) -> Self {
+        Waiting {
+            state: Mutex::new(state),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+        }
+    }
+
+    pub(crate) fn lock(&self) -> MutexGuard<'_, S> {
+        self.state.lock().unwrap()
+    }
+    /// Locks the state once `full` returns false, waiting for pops meanwhile
+    pub(crate) fn wait_for_room(&self, full: impl FnMut(&mut S) -> bool) -> MutexGuard<'_, S> {
+        self.not_full.wait_while(self.lock(), full).unwrap()
+    }
+    /// Locks the state once `empty` returns false, waiting for pushes meanwhile
+    pub(crate) fn wait_for_data(&self, empty: impl FnMut(&mut S) -> bool) -> MutexGuard<'_, S> {
+        self.not_empty.wait_while(self.lock(), empty).unwrap()
+    }
+    /// Same as `wait_for_data`, giving up after `timeout`
+    pub(crate) fn wait_for_data_timeout(
+        &self,
+        timeout: Duration,
+        empty: impl FnMut(&mut S) -> bool,
+    ) -> MutexGuard<'_, S> {
+        self.not_empty
+            .wait_timeout_while(self.lock(), timeout, empty)
+            .unwrap()
+            .0
+    }
+    /// Wakes a pop waiting for data
+    pub(crate) fn pushed(&self) {
+        self.not_empty.notify_one();
+    }
+    /// Wakes a push waiting for room
+    pub(crate) fn popped(&self) {
+        self.not_full.notify_one();
+    }
+
+    fn wake_all(&self) {
+        self.not_empty.notify_all();
+        self.not_full.notify_all();
+    }
+
+    pub(crate) fn into_inner(self) -> S {
+        self.state.into_inner().unwrap()
+    }
+}
+
+/// Stack Data Structure shared between threads, whose operations wait
+/// It has a stack, guarded by a mutex along with whether it is closed,
+/// and the condition variables signalled by pushes and by pops
+/// It has an optional capacity, past which pushes wait
+#[derive(Debug)]
+pub struct BlockingStack<T> {
+    shared: Waiting<State<T>>,
+    capacity: Option<usize>,
+}
+
+#[derive(Debug)]
+struct State<T> {
+    stack: Stack<T>,
+    closed: bool,
+    senders: usize,
+}
+
+impl<T> BlockingStack<T> {
+    /// Initialize a new empty open stack without a capacity, so pushes never wait
+    pub fn new() -> Self {
+        BlockingStack::with_capacity(None)
+    }
+    /// Initialize a new empty open stack holding at most `capacity` elements,
+    /// pushes onto a full stack wait until another thread pops
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0, since every push would wait until the stack is closed
+    pub fn bounded(capacity: usize) -> Self {
+        assert!(
+            capacity > 0,
+            "a BlockingStack needs room for at least one element"
+        );
+        BlockingStack::with_capacity(Some(capacity))
+    }
+
+    fn with_capacity(capacity: Option<usize>) -> Self {
+        BlockingStack {
+            shared: Waiting::new(State {
+                stack: Stack::new(),
+                closed: false,
+                senders: 0,
+            }),
+            capacity,
+        }
+    }
+    /// Pushes the data onto the stack, waiting while the stack is full
+    /// Hands the data back in `Closed` if the stack is or gets closed
+    pub fn push(&self, data: T) -> Result<(), Closed<T>> {
+        let mut state = self
+            .shared
+            .wait_for_room(|state| !state.closed && self.is_full(state));
+        if state.closed {
+            return Err(Closed(data));
+        }
+        state.stack.push(data);
+        drop(state);
+        self.shared.pushed();
+        Ok(())
+    }
+    /// Pops the top off the stack, waiting while the stack is empty
+    /// Returns None once the stack is closed and empty
+    pub fn pop(&self) -> Option<T> {
+        let state = self
+            .shared
+            .wait_for_data(|state| !state.closed && state.stack.head.is_none());
+        self.take(state)
+    }
+    /// Pops the top off the stack, waiting at most `timeout` while the stack is empty
+    /// Returns None if the timeout passes, or once the stack is closed and empty
+    pub fn pop_timeout(&self, timeout: Duration) -> Option<T> {
+        let state = self
+            .shared
+            .wait_for_data_timeout(timeout, |state| !state.closed && state.stack.head.is_none());
+        self.take(state)
+    }
+    /// Pops the top off the stack without waiting
+    /// Returns None if the stack is empty
+    pub fn try_pop(&self) -> Option<T> {
+        self.take(self.lock())
+    }
+    /// Returns the number of elements in the stack
+    pub fn size(&self) -> usize {
+        self.lock().stack.size
+    }
+    /// Returns the maximum number of elements the stack can hold, None if it is unbounded
+    pub fn capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+    /// Closes the stack and wakes every waiting thread: pushes are rejected from now on,
+    /// while the data already in the stack can still be popped
+    pub fn close(&self) {
+        self.lock().closed = true;
+        self.shared.wake_all();
+    }
+    /// Returns true if the stack was closed
+    pub fn is_closed(&self) -> bool {
+        self.lock().closed
+    }
+    /// Splits the stack into a sending and a receiving handle, both cloneable
+    ///
+    /// The stack closes once every `Sender` has been dropped
+    pub fn into_handles(self) -> (Sender<T>, Receiver<T>) {
+        self.lock().senders = 1;
+        let shared = Arc::new(self);
+        (
+            Sender {
+                shared: Arc::clone(&shared),
+            },
+            Receiver { shared },
+        )
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.shared.lock()
+    }
+
+    fn is_full(&self, state: &State<T>) -> bool {
+        self.capacity
+            .is_some_and(|capacity| state.stack.size >= capacity)
+    }
+
+    fn take(&self, mut state: MutexGuard<'_, State<T>>) -> Option<T> {
+        let data = state.stack.pop();
+        drop(state);
+        if data.is_some() && self.capacity.is_some() {
+            self.shared.popped();
+        }
+        data
+    }
+}
+
+impl<T> Default for BlockingStack<T> {
+    fn default() -> Self {
+        BlockingStack::new()
+    }
+}
+
+/// Pushing handle of a BlockingStack, created by `BlockingStack::into_handles`
+///
+/// Cloning adds a sender, and dropping the last one closes the stack
+#[derive(Debug)]
+pub struct Sender<T> {
+    shared: Arc<BlockingStack<T>>,
+}
+
+impl<T> Sender<T> {
+    /// Pushes the data onto the stack, waiting while the stack is full
+    /// Hands the data back in `Closed` if the stack is or gets closed
+    pub fn push(&self, data: T) -> Result<(), Closed<T>> {
+        self.shared.push(data)
+    }
+    /// Closes the stack for every handle
+    pub fn close(&self) {
+        self.shared.close();
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.lock().senders += 1;
+        Sender {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.senders -= 1;
+        if state.senders == 0 {
+            drop(state);
+            self.shared.close();
+        }
+    }
+}
+
+/// Popping handle of a BlockingStack, created by `BlockingStack::into_handles`
+#[derive(Debug)]
+pub struct Receiver<T> {
+    shared: Arc<BlockingStack<T>>,
+}
+
+impl<T> Receiver<T> {
+    /// Pops the top off the stack, waiting while the stack is empty
+    /// Returns None once the stack is closed and empty
+    pub fn pop(&self) -> Option<T> {
+        self.shared.pop()
+    }
+    /// Pops the top off the stack, waiting at most `timeout` while the stack is empty
+    /// Returns None if the timeout passes, or once the stack is closed and empty
+    pub fn pop_timeout(&self, timeout: Duration) -> Option<T> {
+        self.shared.pop_timeout(timeout)
+    }
+    /// Pops the top off the stack without waiting
+    /// Returns None if the stack is empty
+    pub fn try_pop(&self) -> Option<T> {
+        self.shared.try_pop()
+    }
+    /// Returns the number of elements in the stack
+    pub fn size(&self) -> usize {
+        self.shared.size()
+    }
+    /// Returns true if the stack was closed
+    pub fn is_closed(&self) -> bool {
+        self.shared.is_closed()
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        Receiver {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T> Iterator for Receiver<T> {
+    type Item = T;
+
+    /// Pops the top off the stack, waiting while the stack is empty
+    fn next(&mut self) -> Option<Self::Item> {
+        self.pop()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BlockingStack;
+    use crate::Closed;
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn pop_waits_for_push() {
+        let stack = Arc::new(BlockingStack::new());
+        let consumer = {
+            let stack = Arc::clone(&stack);
+            thread::spawn(move || stack.pop())
+        };
+        thread::sleep(Duration::from_millis(20));
+        stack.push(7).unwrap();
+        assert_eq!(Some(7), consumer.join().unwrap());
+        assert_eq!(None, stack.try_pop());
+    }
+
+    #[test]
+    fn pop_timeout() {
+        let stack = BlockingStack::<u8>::new();
+        let start = Instant::now();
+        assert_eq!(None, stack.pop_timeout(Duration::from_millis(20)));
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        stack.push(1).unwrap();
+        assert_eq!(Some(1), stack.pop_timeout(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn bounded_push_waits_for_pop() {
+        let stack = Arc::new(BlockingStack::bounded(1));
+        stack.push(1).unwrap();
+        let producer = {
+            let stack = Arc::clone(&stack);
+            thread::spawn(move || stack.push(2))
+        };
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(1, stack.size());
+        assert_eq!(Some(1), stack.pop());
+        producer.join().unwrap().unwrap();
+        assert_eq!(Some(2), stack.pop());
+        assert_eq!(Some(1), stack.capacity());
+    }
+
+    #[test]
+    #[should_panic(expected = "room for at least one element")]
+    fn bounded_without_room() {
+        BlockingStack::<u8>::bounded(0);
+    }
+
+    #[test]
+    fn close_wakes_waiters() {
+        let stack = Arc::new(BlockingStack::<u8>::bounded(1));
+        stack.push(1).unwrap();
+        let producer = {
+            let stack = Arc::clone(&stack);
+            thread::spawn(move || stack.push(2))
+        };
+        thread::sleep(Duration::from_millis(20));
+        stack.close();
+        assert_eq!(Err(Closed(2)), producer.join().unwrap());
+        assert_eq!(Some(1), stack.pop());
+        assert_eq!(None, stack.pop());
+        assert!(stack.is_closed());
+    }
+
+    #[test]
+    fn handles_close_with_last_sender() {
+        let (sender, receiver) = BlockingStack::bounded(4).into_handles();
+        let producers: Vec<_> = (0..4u64)
+            .map(|i| {
+                let sender = sender.clone();
+                thread::spawn(move || {
+                    for value in 0..100 {
+                        sender.push(i * 100 + value).unwrap();
+                    }
+                })
+            })
+            .collect();
+        drop(sender);
+        let consumers: Vec<_> = (0..2)
+            .map(|_| {
+                let receiver = receiver.clone();
+                thread::spawn(move || receiver.sum::<u64>())
+            })
+            .collect();
+        for producer in producers {
+            producer.join().unwrap();
+        }
+        let total: u64 = consumers.into_iter().map(|c| c.join().unwrap()).sum();
+        assert_eq!((0..400).sum::<u64>(), total);
+        assert!(receiver.is_closed());
+    }
+}
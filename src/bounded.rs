@@ -0,0 +1,291 @@
+//! Bounded Stack Data Structure
+//!
+//! `BoundedStack` holds at most `capacity` elements, and its `Overflow` policy decides
+//! what a push does once the stack is full
+//!
+//! `SyncBoundedStack` shares a `BoundedStack` between threads, waiting the way `BlockingStack`
+//! does, which is what makes `Overflow::Block` useful: a full push waits until another thread pops
+//!
+//! Example
+//! ```rust
+//! use stack::{BoundedStack, Full, Overflow};
+//!
+//! let mut stack = BoundedStack::<i32>::new(2, Overflow::Reject);
+//! assert_eq!(Ok(None), stack.push(1));
+//! assert_eq!(Ok(None), stack.push(2));
+//! assert_eq!(Err(Full(3)), stack.push(3));
+//! assert!(stack.is_full());
+//! ```
+
+#[cfg(feature = "std")]
+use crate::blocking::Waiting;
+pub use crate::Full;
+use crate::Stack;
+use alloc::collections::{vec_deque, VecDeque};
+use core::iter::Rev;
+
+/// What a push does when the stack is already at capacity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Refuse the push and hand the data back inside `Full`
+    Reject,
+    /// Remove the bottom-most element to make room, and return it
+    EvictBottom,
+    /// Wait until another thread pops an element
+    ///
+    /// Only `SyncBoundedStack` can wait, `BoundedStack` treats it like `Reject`
+    /// since nothing else can pop while it is borrowed mutably
+    Block,
+}
+
+/// Stack Data Structure with a maximum size
+/// It has a ring buffer, holding at most capacity elements with the top at its back,
+/// so the bottom can be evicted from its front
+/// It has a policy, applied when pushing onto a full stack
+#[derive(Debug)]
+pub struct BoundedStack<T> {
+    stack: VecDeque<T>,
+    capacity: usize,
+    policy: Overflow,
+}
+
+impl<T> BoundedStack<T> {
+    /// Initialize a new empty stack holding at most `capacity` elements
+    ///
+    /// Example
+    /// ```rust
+    /// # use stack::{BoundedStack, Overflow};
+    /// let new_stack = BoundedStack::<i32>::new(16, Overflow::EvictBottom);
+    /// ```
+    pub fn new(capacity: usize, policy: Overflow) -> Self {
+        BoundedStack {
+            stack: VecDeque::new(),
+            capacity,
+            policy,
+        }
+    }
+    /// Get a reference to data in the head of the stack
+    /// Returns None if the stack is empty
+    pub fn peek(&self) -> Option<&T> {
+        self.stack.back()
+    }
+    /// Get a mutable reference to data in the head of the stack
+    /// Returns None if the stack is empty
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        self.stack.back_mut()
+    }
+    /// Pops the top off the stack and returns the data it contains
+    /// Returns None if the stack is empty
+    pub fn pop(&mut self) -> Option<T> {
+        self.stack.pop_back()
+    }
+    /// Pushes the data onto the stack, applying the overflow policy if it is full
+    ///
+    /// Returns the evicted bottom-most data under `Overflow::EvictBottom` [O(1) operation],
+    /// and hands the data back in `Full` under `Overflow::Reject` or `Overflow::Block`
+    ///
+    /// Example
+    /// ```rust
+    /// # use stack::{BoundedStack, Overflow};
+    /// let mut new_stack = BoundedStack::<i32>::new(1, Overflow::EvictBottom);
+    /// new_stack.push(1).unwrap();
+    /// assert_eq!(Ok(Some(1)), new_stack.push(2));
+    /// ```
+    pub fn push(&mut self, data: T) -> Result<Option<T>, Full<T>> {
+        if !self.is_full() {
+            self.stack.push_back(data);
+            return Ok(None);
+        }
+        match self.policy {
+            Overflow::Reject | Overflow::Block => Err(Full(data)),
+            Overflow::EvictBottom => {
+                self.stack.push_back(data);
+                Ok(self.stack.pop_front())
+            }
+        }
+    }
+    /// Returns the number of elements in the stack
+    pub fn size(&self) -> usize {
+        self.stack.len()
+    }
+    /// Returns the maximum number of elements the stack can hold
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+    /// Returns the overflow policy of the stack
+    pub fn policy(&self) -> Overflow {
+        self.policy
+    }
+    /// Returns true if a push would have to apply the overflow policy
+    pub fn is_full(&self) -> bool {
+        self.stack.len() >= self.capacity
+    }
+    /// Returns the number of elements that can be pushed before the stack is full
+    pub fn remaining(&self) -> usize {
+        self.capacity.saturating_sub(self.stack.len())
+    }
+    /// Returns an iterator over references to the data, from the top of the stack to the bottom
+    pub fn iter(&self) -> Rev<vec_deque::Iter<'_, T>> {
+        self.stack.iter().rev()
+    }
+    /// Removes all the elements from the stack
+    pub fn clear(&mut self) {
+        self.stack.clear();
+    }
+    /// Consumes the bounded stack, returning its data as an unbounded stack [O(n) operation]
+    pub fn into_inner(self) -> Stack<T> {
+        self.stack.into_iter().collect()
+    }
+}
+
+impl<T: PartialEq> BoundedStack<T> {
+    /// Searches for data in the whole stack [O(n) operation]
+    /// Returns its position counted from the bottom like `Stack::search`, or None if the data is not found
+    pub fn search(&self, data: T) -> Option<usize> {
+        self.stack
+            .iter()
+            .rposition(|value| *value == data)
+            .map(|index| index + 1)
+    }
+}
+
+impl<'a, T> IntoIterator for &'a BoundedStack<T> {
+    type Item = &'a T;
+    type IntoIter = Rev<vec_deque::Iter<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Bounded Stack Data Structure shared between threads
+/// It has a bounded stack, guarded by a mutex along with the condition variable
+/// a push waits on for room under `Overflow::Block`
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct SyncBoundedStack<T> {
+    shared: Waiting<BoundedStack<T>>,
+}
+
+#[cfg(feature = "std")]
+impl<T> SyncBoundedStack<T> {
+    /// Initialize a new empty stack holding at most `capacity` elements
+    pub fn new(capacity: usize, policy: Overflow) -> Self {
+        SyncBoundedStack {
+            shared: Waiting::new(BoundedStack::new(capacity, policy)),
+        }
+    }
+    /// Pops the top off the stack and returns the data it contains
+    /// Returns None if the stack is empty
+    pub fn pop(&self) -> Option<T> {
+        let data = self.shared.lock().pop();
+        if data.is_some() {
+            self.shared.popped();
+        }
+        data
+    }
+    /// Pushes the data onto the stack, applying the overflow policy if it is full
+    ///
+    /// Under `Overflow::Block` this waits until another thread pops, and never fails
+    pub fn push(&self, data: T) -> Result<Option<T>, Full<T>> {
+        self.shared
+            .wait_for_room(|stack| stack.policy == Overflow::Block && stack.is_full())
+            .push(data)
+    }
+    /// Returns the number of elements in the stack
+    pub fn size(&self) -> usize {
+        self.shared.lock().size()
+    }
+    /// Returns the maximum number of elements the stack can hold
+    pub fn capacity(&self) -> usize {
+        self.shared.lock().capacity()
+    }
+    /// Returns true if a push would have to apply the overflow policy
+    pub fn is_full(&self) -> bool {
+        self.shared.lock().is_full()
+    }
+    /// Returns the number of elements that can be pushed before the stack is full
+    pub fn remaining(&self) -> usize {
+        self.shared.lock().remaining()
+    }
+    /// Consumes the shared stack, returning the bounded stack inside
+    pub fn into_inner(self) -> BoundedStack<T> {
+        self.shared.into_inner()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Clone> SyncBoundedStack<T> {
+    /// Get a clone of the data in the head of the stack
+    /// Returns None if the stack is empty
+    pub fn peek_cloned(&self) -> Option<T> {
+        self.shared.lock().peek().cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BoundedStack, Full, Overflow};
+
+    #[test]
+    fn reject() {
+        let mut stack = BoundedStack::<u8>::new(2, Overflow::Reject);
+        assert_eq!(2, stack.remaining());
+        assert_eq!(Ok(None), stack.push(3));
+        assert_eq!(Ok(None), stack.push(6));
+        assert!(stack.is_full());
+        assert_eq!(0, stack.remaining());
+        assert_eq!(Err(Full(9)), stack.push(9));
+        assert_eq!(Some(&6), stack.peek());
+        assert_eq!(Some(6), stack.pop());
+        assert_eq!(Ok(None), stack.push(9));
+        assert_eq!(Some(1), stack.search(3));
+    }
+
+    #[test]
+    fn evict_bottom() {
+        let mut stack = BoundedStack::<u8>::new(3, Overflow::EvictBottom);
+        for i in 1..=3 {
+            assert_eq!(Ok(None), stack.push(i));
+        }
+        assert_eq!(Ok(Some(1)), stack.push(4));
+        assert_eq!(Ok(Some(2)), stack.push(5));
+        assert_eq!(3, stack.size());
+        assert_eq!(vec![&5, &4, &3], stack.iter().collect::<Vec<_>>());
+
+        assert_eq!(Some(3), stack.search(5));
+        assert_eq!(
+            vec![&5, &4, &3],
+            stack.into_inner().iter().collect::<Vec<_>>()
+        );
+
+        let mut empty = BoundedStack::<u8>::new(0, Overflow::EvictBottom);
+        assert_eq!(Ok(Some(7)), empty.push(7));
+        assert_eq!(0, empty.size());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn block() {
+        use super::SyncBoundedStack;
+        use std::sync::Arc;
+        use std::thread;
+        use std::time::Duration;
+
+        let stack = Arc::new(SyncBoundedStack::<u8>::new(1, Overflow::Block));
+        stack.push(1).unwrap();
+        let pusher = {
+            let stack = Arc::clone(&stack);
+            thread::spawn(move || stack.push(2))
+        };
+        thread::sleep(Duration::from_millis(50));
+        assert_eq!(Some(1), stack.peek_cloned());
+        assert_eq!(Some(1), stack.pop());
+        assert_eq!(Ok(None), pusher.join().unwrap());
+        assert_eq!(Some(2), stack.pop());
+        assert_eq!(1, stack.remaining());
+
+        let mut unshared = BoundedStack::<u8>::new(0, Overflow::Block);
+        assert_eq!(Err(Full(1)), unshared.push(1));
+    }
+}
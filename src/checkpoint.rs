@@ -0,0 +1,340 @@
+//! Checkpoints and rollback for Stack
+//!
+//! While a checkpoint is open the stack journals every push and pop, keeping a clone of
+//! each popped value, so `Stack::rollback` can replay the journal backwards and restore
+//! the exact contents the stack had when the checkpoint was taken
+//!
+//! Example
+//! ```rust
+//! use stack::Stack;
+//!
+//! let mut my_stack = Stack::<i32>::new();
+//! my_stack.push(1);
+//! let checkpoint = my_stack.checkpoint();
+//! my_stack.pop();
+//! my_stack.push(2);
+//! my_stack.push(3);
+//! my_stack.rollback(checkpoint);
+//! assert_eq!(vec![&1], my_stack.iter().collect::<Vec<_>>());
+//! ```
+
+use crate::Stack;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Source of the ids telling the journals of different stacks apart
+static NEXT_JOURNAL: AtomicUsize = AtomicUsize::new(0);
+
+/// A mark in the history of a Stack, created by `Stack::checkpoint`
+/// It has a journal, the id of the journal of the stack it was taken on
+/// It has a mark, the length of the journal when it was taken
+/// It has a level, how many checkpoints were open including this one
+///
+/// It must be handed back to `Stack::rollback` or `Stack::commit` of the same stack
+#[derive(Debug)]
+#[must_use = "a checkpoint stays open, and the stack keeps journaling, until it is rolled back or committed"]
+pub struct Checkpoint {
+    journal: usize,
+    mark: usize,
+    level: usize,
+}
+
+/// The pushes and pops done since the outermost open checkpoint
+#[derive(Debug)]
+pub(crate) struct Journal<T> {
+    id: usize,
+    entries: Vec<Entry<T>>,
+    open: usize,
+    clone: fn(&T) -> T,
+}
+
+#[derive(Debug)]
+enum Entry<T> {
+    Pushed,
+    Popped(T),
+}
+
+impl<T> Journal<T> {
+    pub(crate) fn pushed(&mut self) {
+        self.entries.push(Entry::Pushed);
+    }
+
+    pub(crate) fn popped(&mut self, data: &T) {
+        self.entries.push(Entry::Popped((self.clone)(data)));
+    }
+
+    fn close(&mut self, checkpoint: Checkpoint) {
+        assert!(
+            checkpoint.journal == self.id,
+            "checkpoint was taken on another stack, or is no longer open"
+        );
+        assert!(
+            checkpoint.level <= self.open && checkpoint.mark <= self.entries.len(),
+            "checkpoint is no longer open"
+        );
+        self.open = checkpoint.level - 1;
+    }
+}
+
+impl<T: Clone> Stack<T> {
+    /// Marks the current contents of the stack, so they can be restored by `rollback`
+    ///
+    /// Checkpoints nest: rolling back or committing one also closes every checkpoint taken after it
+    ///
+    /// Only `push` and `pop`, and `clear` which pops, are journaled; `append`, `split_off`,
+    /// `retain`, `dedup`, `reverse` and `cursor_mut` panic while a checkpoint is open,
+    /// and data edited in place through mutable references is not undone by a rollback
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        let journal = self.journal.get_or_insert_with(|| {
+            Box::new(Journal {
+                id: NEXT_JOURNAL.fetch_add(1, Ordering::Relaxed),
+                entries: Vec::new(),
+                open: 0,
+                clone: T::clone,
+            })
+        });
+        journal.open += 1;
+        Checkpoint {
+            journal: journal.id,
+            mark: journal.entries.len(),
+            level: journal.open,
+        }
+    }
+    /// Runs the closure on the stack, rolling back everything it did if it returns `Err` or panics
+    ///
+    /// The closure runs under a checkpoint, so it can only edit the stack with journaled operations
+    ///
+    /// Example
+    /// ```rust
+    /// # use stack::Stack;
+    /// # let mut new_stack = Stack::<i32>::new();
+    /// # new_stack.push(1);
+    /// let result: Result<(), &str> = new_stack.transaction(|s| {
+    ///     s.pop();
+    ///     s.push(2);
+    ///     Err("dead end")
+    /// });
+    /// assert_eq!(Some(&1), new_stack.peek());
+    /// ```
+    pub fn transaction<R, E, F>(&mut self, f: F) -> Result<R, E>
+    where
+        F: FnOnce(&mut Stack<T>) -> Result<R, E>,
+    {
+        let checkpoint = self.checkpoint();
+        let mut guard = RollbackOnDrop {
+            stack: self,
+            checkpoint: Some(checkpoint),
+        };
+        let result = f(guard.stack);
+        if result.is_ok() {
+            let checkpoint = guard.checkpoint.take().unwrap();
+            guard.stack.commit(checkpoint);
+        }
+        result
+    }
+}
+
+impl<T> Stack<T> {
+    /// Panics if a checkpoint is open, for edits the journal cannot replay
+    pub(crate) fn assert_no_checkpoint(&self, operation: &str) {
+        assert!(
+            self.journal.is_none(),
+            "cannot {} while a checkpoint is open",
+            operation
+        );
+    }
+    /// Restores the contents and size the stack had when the checkpoint was taken,
+    /// closing it and every checkpoint taken after it
+    ///
+    /// # Panics
+    ///
+    /// Panics if the checkpoint was taken on another stack, or was already closed
+    /// by rolling back or committing an outer one
+    pub fn rollback(&mut self, checkpoint: Checkpoint) {
+        let mark = checkpoint.mark;
+        self.journal
+            .as_mut()
+            .expect("checkpoint is no longer open")
+            .close(checkpoint);
+        let mut journal = self.journal.take().unwrap();
+        // The journal is taken out, so replaying it is not journaled again
+        while journal.entries.len() > mark {
+            match journal.entries.pop() {
+                Some(Entry::Pushed) => {
+                    self.pop();
+                }
+                Some(Entry::Popped(data)) => self.push(data),
+                None => break,
+            }
+        }
+        if journal.open > 0 {
+            self.journal = Some(journal);
+        }
+    }
+    /// Keeps the changes made since the checkpoint was taken,
+    /// closing it and every checkpoint taken after it
+    ///
+    /// The changes can still be undone by rolling back a checkpoint taken before this one
+    ///
+    /// # Panics
+    ///
+    /// Panics if the checkpoint was taken on another stack, or was already closed
+    /// by rolling back or committing an outer one
+    pub fn commit(&mut self, checkpoint: Checkpoint) {
+        let journal = self.journal.as_mut().expect("checkpoint is no longer open");
+        journal.close(checkpoint);
+        if journal.open == 0 {
+            self.journal = None;
+        }
+    }
+}
+
+/// Rolls the stack back to the checkpoint unless it was taken out,
+/// which is what undoes a transaction whose closure panicked
+struct RollbackOnDrop<'a, T> {
+    stack: &'a mut Stack<T>,
+    checkpoint: Option<Checkpoint>,
+}
+
+impl<T> Drop for RollbackOnDrop<'_, T> {
+    fn drop(&mut self) {
+        if let Some(checkpoint) = self.checkpoint.take() {
+            self.stack.rollback(checkpoint);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Stack;
+    use std::panic::{self, AssertUnwindSafe};
+
+    fn sample() -> Stack<String> {
+        let mut stack = Stack::new();
+        for value in &["a", "b", "c"] {
+            stack.push(value.to_string());
+        }
+        stack
+    }
+
+    fn contents(stack: &Stack<String>) -> Vec<&str> {
+        stack.iter().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn rollback_and_commit() {
+        let mut stack = sample();
+        let outer = stack.checkpoint();
+        stack.pop();
+        stack.pop();
+        stack.push("x".to_string());
+        let inner = stack.checkpoint();
+        stack.clear();
+        assert_eq!(0, stack.size);
+        stack.rollback(inner);
+        assert_eq!(vec!["x", "a"], contents(&stack));
+
+        let inner = stack.checkpoint();
+        stack.push("y".to_string());
+        stack.commit(inner);
+        assert_eq!(3, stack.size);
+        stack.rollback(outer);
+        assert_eq!(vec!["c", "b", "a"], contents(&stack));
+        assert_eq!(3, stack.size);
+
+        let checkpoint = stack.checkpoint();
+        stack.pop();
+        stack.commit(checkpoint);
+        assert!(stack.journal.is_none());
+        assert_eq!(vec!["b", "a"], contents(&stack));
+    }
+
+    #[test]
+    #[should_panic(expected = "no longer open")]
+    fn closed_checkpoint() {
+        let mut stack = sample();
+        let outer = stack.checkpoint();
+        let inner = stack.checkpoint();
+        stack.rollback(outer);
+        stack.rollback(inner);
+    }
+
+    #[test]
+    fn foreign_checkpoint() {
+        let mut stack = sample();
+        let mut other = sample();
+        let mine = stack.checkpoint();
+        let theirs = other.checkpoint();
+        stack.pop();
+        let unwound = panic::catch_unwind(AssertUnwindSafe(|| stack.rollback(theirs)));
+        assert!(unwound.is_err());
+        // The failed rollback left the stack and its own checkpoint untouched
+        assert_eq!(vec!["b", "a"], contents(&stack));
+        stack.rollback(mine);
+        assert_eq!(vec!["c", "b", "a"], contents(&stack));
+    }
+
+    #[test]
+    fn transaction() {
+        let mut stack = sample();
+        let result: Result<usize, ()> = stack.transaction(|s| {
+            s.pop();
+            Ok(s.size)
+        });
+        assert_eq!(Ok(2), result);
+        assert_eq!(vec!["b", "a"], contents(&stack));
+
+        let result: Result<(), &str> = stack.transaction(|s| {
+            s.push("z".to_string());
+            s.transaction(|s| {
+                s.clear();
+                Ok::<(), ()>(())
+            })
+            .unwrap();
+            Err("backtrack")
+        });
+        assert_eq!(Err("backtrack"), result);
+        assert_eq!(vec!["b", "a"], contents(&stack));
+
+        let unwound = panic::catch_unwind(AssertUnwindSafe(|| {
+            stack.transaction(|s| -> Result<(), ()> {
+                s.pop();
+                panic!("solver bug");
+            })
+        }));
+        assert!(unwound.is_err());
+        assert_eq!(vec!["b", "a"], contents(&stack));
+        assert!(stack.journal.is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot reverse while a checkpoint is open")]
+    fn unjournaled_edit() {
+        let mut stack = sample();
+        let _checkpoint = stack.checkpoint();
+        stack.reverse();
+    }
+
+    #[test]
+    fn unjournaled_edit_in_transaction() {
+        let mut stack = sample();
+        let mut other = sample();
+        let unwound = panic::catch_unwind(AssertUnwindSafe(|| {
+            stack.transaction(|s| -> Result<(), ()> {
+                s.pop();
+                s.retain(|value| value == "a");
+                Ok(())
+            })
+        }));
+        assert!(unwound.is_err());
+        assert_eq!(vec!["c", "b", "a"], contents(&stack));
+
+        let checkpoint = other.checkpoint();
+        let unwound = panic::catch_unwind(AssertUnwindSafe(|| stack.append(&mut other)));
+        assert!(unwound.is_err());
+        other.rollback(checkpoint);
+        assert_eq!(3, stack.size);
+        assert_eq!(vec!["c", "b", "a"], contents(&other));
+    }
+}
@@ -0,0 +1,406 @@
+//! Lock-free Concurrent Stack Data Structure
+//!
+//! `ConcurrentStack` implements Treiber's algorithm: every operation swaps the head
+//! of the stack with a single compare-and-swap, so it can be shared between threads
+//! without a `Mutex`
+//!
+//! Popped tiles are reclaimed through `crossbeam-epoch`, so a thread still reading
+//! a tile never sees it freed under it, and a freed address is never reused while
+//! another thread could compare against it (no ABA)
+//!
+//! No operation ever waits for another thread: a pop racing with `peek_cloned` on the
+//! same tile returns a clone of the value, and the value itself is dropped once the
+//! peeking threads are done with it
+//!
+//! Waiting is the only way a pop could hand back the pushed value itself, since a peek
+//! runs `T::clone` on it in place and that cannot be interrupted
+//!
+//! Example
+//! ```rust
+//! use stack::ConcurrentStack;
+//! use std::sync::Arc;
+//! use std::thread;
+//!
+//! let stack = Arc::new(ConcurrentStack::<i32>::new());
+//! let pusher = {
+//!     let stack = Arc::clone(&stack);
+//!     thread::spawn(move || stack.push(5))
+//! };
+//! pusher.join().unwrap();
+//! assert_eq!(Some(5), stack.peek_cloned());
+//! assert_eq!(Some(5), stack.pop());
+//! ```
+//!
+//! The model tests run under `loom`:
+//! `RUSTFLAGS="--cfg loom --cfg crossbeam_loom" cargo test --release --lib concurrent`
+
+use crossbeam_epoch::{self as epoch, Atomic, Owned, Shared};
+use std::fmt;
+use std::mem::ManuallyDrop;
+
+#[cfg(loom)]
+use loom::{
+    cell::UnsafeCell,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+#[cfg(not(loom))]
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Set in `Tile::readers` once a pop has unlinked the tile and claimed its value
+const TAKEN: usize = 1 << (usize::BITS - 1);
+
+/// Lock-free Stack Data Structure
+/// It has a head, that points to the top of the stack
+/// It has a len, updated on every push and pop
+/// It has a clone, the `T::clone` registered by `peek_cloned` for pops that race with it
+pub struct ConcurrentStack<T> {
+    head: Atomic<Tile<T>>,
+    len: AtomicUsize,
+    clone: Atomic<CloneFn<T>>,
+}
+
+/// `T::clone`, which pops can only name through a `peek_cloned` that registered it
+struct CloneFn<T>(fn(&T) -> T);
+
+struct Tile<T> {
+    value: UnsafeCell<ManuallyDrop<T>>,
+    next: Atomic<Tile<T>>,
+    /// Number of `peek_cloned` calls currently cloning the value, plus the `TAKEN` flag
+    readers: AtomicUsize,
+}
+
+impl<T> ConcurrentStack<T> {
+    /// Initialize a new stack with its head pointing to None and with zero len
+    pub fn new() -> Self {
+        ConcurrentStack {
+            head: Atomic::null(),
+            len: AtomicUsize::new(0),
+            clone: Atomic::null(),
+        }
+    }
+    /// Pushes a new tile with the desired data onto the stack
+    ///
+    /// Increases the len of stack by 1 unit
+    pub fn push(&self, data: T) {
+        let mut tile = Owned::new(Tile {
+            value: UnsafeCell::new(ManuallyDrop::new(data)),
+            next: Atomic::null(),
+            readers: AtomicUsize::new(0),
+        });
+        // Counted before the tile is published, so a pop can never see len at zero
+        self.len.fetch_add(1, Ordering::Relaxed);
+        let guard = epoch::pin();
+        loop {
+            let head = self.head.load(Ordering::Relaxed, &guard);
+            tile.next.store(head, Ordering::Relaxed);
+            match self.head.compare_exchange(
+                head,
+                tile,
+                Ordering::Release,
+                Ordering::Relaxed,
+                &guard,
+            ) {
+                Ok(_) => return,
+                Err(e) => tile = e.new,
+            }
+        }
+    }
+    /// Pops the top off the stack and returns the data it contains
+    /// Returns None if the stack is empty
+    ///
+    /// Reduces the len of stack by 1 unit
+    ///
+    /// If a `peek_cloned` is still cloning the popped value, the pop returns its own
+    /// `T::clone` of it rather than waiting, and the pushed value is dropped once the
+    /// peek is done; types whose clone is not interchangeable with the original, or is
+    /// expensive, should not be peeked while they are popped
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T::clone` panics in that case, the pushed value is still dropped
+    pub fn pop(&self) -> Option<T> {
+        let guard = epoch::pin();
+        loop {
+            let head = self.head.load(Ordering::Acquire, &guard);
+            // The guard keeps the tile alive even if another thread pops it meanwhile
+            let tile = unsafe { head.as_ref() }?;
+            let next = tile.next.load(Ordering::Relaxed, &guard);
+            if self
+                .head
+                .compare_exchange(head, next, Ordering::Relaxed, Ordering::Relaxed, &guard)
+                .is_err()
+            {
+                continue;
+            }
+            self.len.fetch_sub(1, Ordering::Relaxed);
+            // Only the thread that unlinked the tile reaches this point, and no
+            // reader can start cloning once TAKEN is set
+            if tile.readers.fetch_or(TAKEN, Ordering::AcqRel) == 0 {
+                let data = tile
+                    .value
+                    .with_mut(|value| unsafe { ManuallyDrop::take(&mut *value) });
+                unsafe { guard.defer_destroy(head) };
+                return Some(data);
+            }
+            // Peeks are still cloning the value: clone it alongside them, and drop it
+            // once every thread pinned now, which includes them, has unpinned
+            unsafe {
+                guard.defer_unchecked(move || {
+                    let tile = head.into_owned();
+                    tile.value.with_mut(|value| ManuallyDrop::drop(&mut *value));
+                });
+            }
+            // The readers registered `T::clone` before counting themselves,
+            // and the `fetch_or` above read their count
+            let clone = unsafe { self.clone.load(Ordering::Acquire, &guard).deref() }.0;
+            return Some(tile.value.with(|value| unsafe { clone(&*value) }));
+        }
+    }
+    /// Returns the number of elements in the stack
+    ///
+    /// Other threads may push or pop at any time, so the result can be stale by the time it is used
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+    /// Returns true if the stack holds no elements
+    pub fn is_empty(&self) -> bool {
+        let guard = epoch::pin();
+        self.head.load(Ordering::Acquire, &guard).is_null()
+    }
+}
+
+impl<T: Clone> ConcurrentStack<T> {
+    /// Get a clone of the data in the head of the stack
+    /// Returns None if the stack is empty
+    ///
+    /// A reference cannot be handed out since another thread may pop the value at any time
+    ///
+    /// A pop racing with the clone returns a clone of the value too, instead of waiting for it
+    pub fn peek_cloned(&self) -> Option<T> {
+        let guard = epoch::pin();
+        if self.clone.load(Ordering::Acquire, &guard).is_null() {
+            // Every peek registers the same function, so losing this race changes nothing
+            let _ = self.clone.compare_exchange(
+                Shared::null(),
+                Owned::new(CloneFn(T::clone)),
+                Ordering::Release,
+                Ordering::Acquire,
+                &guard,
+            );
+        }
+        loop {
+            let head = self.head.load(Ordering::Acquire, &guard);
+            let tile = unsafe { head.as_ref() }?;
+            let reading = Reading(&tile.readers);
+            if tile.readers.fetch_add(1, Ordering::AcqRel) & TAKEN == 0 {
+                // A pop that sees `reading` leaves the value in the tile until this thread unpins
+                let data = tile.value.with(|value| unsafe { T::clone(&*value) });
+                drop(reading);
+                return Some(data);
+            }
+            // The tile was popped meanwhile, so the head has moved on
+        }
+    }
+}
+
+/// Marks a `peek_cloned` in progress on a tile, released even if `T::clone` panics
+struct Reading<'a>(&'a AtomicUsize);
+
+impl Drop for Reading<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Release);
+    }
+}
+
+// Values move between threads through push and pop, and peek_cloned reads them in place
+unsafe impl<T: Send> Send for ConcurrentStack<T> {}
+unsafe impl<T: Send + Sync> Sync for ConcurrentStack<T> {}
+
+impl<T> Default for ConcurrentStack<T> {
+    fn default() -> Self {
+        ConcurrentStack::new()
+    }
+}
+
+impl<T> fmt::Debug for ConcurrentStack<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConcurrentStack")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/// Unlinks and frees the tiles one at a time, without recursing through the chain
+impl<T> Drop for ConcurrentStack<T> {
+    fn drop(&mut self) {
+        // `&mut self` guarantees no other thread can still reach the tiles
+        unsafe {
+            let guard = epoch::unprotected();
+            let mut ptr = self.head.load(Ordering::Relaxed, guard);
+            while !ptr.is_null() {
+                let tile = ptr.into_owned();
+                ptr = tile.next.load(Ordering::Relaxed, guard);
+                tile.value.with_mut(|value| ManuallyDrop::drop(&mut *value));
+            }
+            let clone = self.clone.load(Ordering::Relaxed, guard);
+            if !clone.is_null() {
+                drop(clone.into_owned());
+            }
+        }
+    }
+}
+
+/// `std::cell::UnsafeCell` with the closure based API of `loom::cell::UnsafeCell`
+#[cfg(not(loom))]
+struct UnsafeCell<T>(std::cell::UnsafeCell<T>);
+
+#[cfg(not(loom))]
+impl<T> UnsafeCell<T> {
+    fn new(data: T) -> Self {
+        UnsafeCell(std::cell::UnsafeCell::new(data))
+    }
+
+    fn with<R>(&self, f: impl FnOnce(*const T) -> R) -> R {
+        f(self.0.get())
+    }
+
+    fn with_mut<R>(&self, f: impl FnOnce(*mut T) -> R) -> R {
+        f(self.0.get())
+    }
+}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use super::ConcurrentStack;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn basics() {
+        let stack = ConcurrentStack::<u8>::new();
+        stack.push(3);
+        stack.push(6);
+        stack.push(9);
+        assert_eq!(3, stack.len());
+        assert_eq!(Some(9), stack.peek_cloned());
+        assert_eq!(Some(9), stack.pop());
+        assert_eq!(Some(6), stack.pop());
+        assert_eq!(Some(3), stack.pop());
+        assert_eq!(None, stack.pop());
+        assert_eq!(None, stack.peek_cloned());
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    fn many_threads() {
+        let stack = Arc::new(ConcurrentStack::<String>::new());
+        let handles: Vec<_> = (0..8)
+            .map(|t| {
+                let stack = Arc::clone(&stack);
+                thread::spawn(move || {
+                    let mut popped = Vec::new();
+                    for i in 0..1000 {
+                        stack.push(format!("{}-{}", t, i));
+                        stack.peek_cloned();
+                        popped.extend(stack.pop());
+                    }
+                    popped
+                })
+            })
+            .collect();
+        let mut popped: Vec<String> = handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect();
+        popped.sort();
+        popped.dedup();
+        assert_eq!(8000, popped.len());
+        assert_eq!(0, stack.len());
+    }
+
+    #[test]
+    fn drop_deep_stack() {
+        let stack = ConcurrentStack::<Box<u32>>::new();
+        for i in 0..1_000_000 {
+            stack.push(Box::new(i));
+        }
+        assert_eq!(1_000_000, stack.len());
+        drop(stack);
+    }
+}
+
+#[cfg(all(test, loom))]
+mod loom_tests {
+    use super::ConcurrentStack;
+    use loom::sync::Arc;
+    use loom::thread;
+
+    fn model(f: impl Fn() + Sync + Send + 'static) {
+        let mut builder = loom::model::Builder::new();
+        builder.preemption_bound = Some(3);
+        builder.check(f);
+    }
+
+    #[test]
+    fn push_pop() {
+        model(|| {
+            let stack = Arc::new(ConcurrentStack::new());
+            let other = Arc::clone(&stack);
+            let handle = thread::spawn(move || {
+                other.push(1);
+                other.pop()
+            });
+            stack.push(2);
+            let mine = stack.pop();
+            let theirs = handle.join().unwrap();
+            let mut popped = vec![mine.unwrap(), theirs.unwrap()];
+            popped.sort();
+            assert_eq!(vec![1, 2], popped);
+            assert!(stack.is_empty());
+            assert_eq!(0, stack.len());
+        });
+    }
+
+    #[test]
+    fn no_aba() {
+        // One thread pops A while the other pops A and B and pushes A back:
+        // a stale compare-and-swap on A would resurrect B
+        model(|| {
+            let stack = Arc::new(ConcurrentStack::new());
+            stack.push("b".to_string());
+            stack.push("a".to_string());
+            let other = Arc::clone(&stack);
+            let handle = thread::spawn(move || {
+                let a = other.pop().unwrap();
+                let b = other.pop();
+                other.push(a);
+                b
+            });
+            let first = stack.pop();
+            let b = handle.join().unwrap();
+            let mut all: Vec<String> = first.into_iter().chain(b).collect();
+            while let Some(value) = stack.pop() {
+                all.push(value);
+            }
+            all.sort();
+            assert_eq!(vec!["a".to_string(), "b".to_string()], all);
+        });
+    }
+
+    #[test]
+    fn peek_while_popping() {
+        // The pop never waits for the peek, and the popped String is only dropped
+        // once the peek is done cloning it
+        model(|| {
+            let stack = Arc::new(ConcurrentStack::new());
+            stack.push("value".to_string());
+            let other = Arc::clone(&stack);
+            let handle = thread::spawn(move || other.peek_cloned());
+            assert_eq!(Some("value".to_string()), stack.pop());
+            if let Some(peeked) = handle.join().unwrap() {
+                assert_eq!("value", peeked);
+            }
+            assert!(stack.is_empty());
+        });
+    }
+}
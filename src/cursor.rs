@@ -0,0 +1,321 @@
+//! Cursors over the Tile chain of a Stack
+//!
+//! A cursor starts at the top of the stack and walks down one tile at a time,
+//! `CursorMut` can also edit the chain where it stands without popping what lies above
+//!
+//! Example
+//! ```rust
+//! use stack::Stack;
+//!
+//! let mut my_stack = Stack::<i32>::new();
+//! my_stack.push(1);
+//! my_stack.push(3);
+//! let mut cursor = my_stack.cursor_mut();
+//! cursor.insert_after(2);
+//! cursor.move_next();
+//! assert_eq!(Some(&mut 2), cursor.peek_mut());
+//! assert_eq!(vec![&3, &2, &1], my_stack.iter().collect::<Vec<_>>());
+//! ```
+
+use crate::{Stack, Tile};
+use core::marker::PhantomData;
+use core::ptr::NonNull;
+
+/// Read-only cursor over a Stack, created by `Stack::cursor`
+/// It has a current tile, None once the cursor has moved past the bottom
+/// It has a depth, the number of tiles above the current one
+pub struct Cursor<'a, T> {
+    current: Option<NonNull<Tile<T>>>,
+    depth: usize,
+    _marker: PhantomData<&'a Tile<T>>,
+}
+
+impl<'a, T> Cursor<'a, T> {
+    pub(crate) fn new(stack: &'a Stack<T>) -> Self {
+        Cursor {
+            current: stack.head,
+            depth: 0,
+            _marker: PhantomData,
+        }
+    }
+    /// Get a reference to data under the cursor
+    /// Returns None if the cursor is past the bottom of the stack
+    pub fn peek(&self) -> Option<&'a T> {
+        // The stack is borrowed for 'a, so its tiles stay live and unchanged
+        self.current.map(|tile| unsafe { &(*tile.as_ptr()).value })
+    }
+    /// Get a reference to data right below the cursor
+    /// Returns None if the cursor is on the bottom tile or past it
+    pub fn peek_next(&self) -> Option<&'a T> {
+        self.current
+            .and_then(|tile| unsafe { (*tile.as_ptr()).next })
+            .map(|tile| unsafe { &(*tile.as_ptr()).value })
+    }
+    /// Moves the cursor one tile towards the bottom of the stack
+    /// Returns false, without moving, if the cursor is already past the bottom
+    pub fn move_next(&mut self) -> bool {
+        match self.current {
+            Some(tile) => {
+                self.current = unsafe { (*tile.as_ptr()).next };
+                self.depth += 1;
+                true
+            }
+            None => false,
+        }
+    }
+    /// Returns the number of tiles above the cursor, 0 at the top of the stack
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+}
+
+impl<T> Clone for Cursor<'_, T> {
+    fn clone(&self) -> Self {
+        Cursor {
+            current: self.current,
+            depth: self.depth,
+            _marker: PhantomData,
+        }
+    }
+}
+
+// The cursor only hands out shared references to the data
+unsafe impl<T: Sync> Send for Cursor<'_, T> {}
+unsafe impl<T: Sync> Sync for Cursor<'_, T> {}
+
+/// Cursor over a Stack that can edit the chain, created by `Stack::cursor_mut`
+/// It has the stack, borrowed mutably for as long as the cursor lives
+/// It has a current tile, None once the cursor has moved past the bottom
+/// It has the tile above the current one, None at the top of the stack
+/// It has a depth, the number of tiles above the current one
+///
+/// Every edit keeps the size and the tail of the stack up to date
+pub struct CursorMut<'a, T> {
+    stack: &'a mut Stack<T>,
+    current: Option<NonNull<Tile<T>>>,
+    above: Option<NonNull<Tile<T>>>,
+    depth: usize,
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    pub(crate) fn new(stack: &'a mut Stack<T>) -> Self {
+        CursorMut {
+            current: stack.head,
+            stack,
+            above: None,
+            depth: 0,
+        }
+    }
+    /// Get a reference to data under the cursor
+    /// Returns None if the cursor is past the bottom of the stack
+    pub fn peek(&self) -> Option<&T> {
+        // Every link points to a live tile owned by the borrowed stack
+        self.current.map(|tile| unsafe { &(*tile.as_ptr()).value })
+    }
+    /// Get a mutable reference to data under the cursor
+    /// Returns None if the cursor is past the bottom of the stack
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        self.current
+            .map(|tile| unsafe { &mut (*tile.as_ptr()).value })
+    }
+    /// Get a reference to data right below the cursor
+    /// Returns None if the cursor is on the bottom tile or past it
+    pub fn peek_next(&self) -> Option<&T> {
+        self.current
+            .and_then(|tile| unsafe { (*tile.as_ptr()).next })
+            .map(|tile| unsafe { &(*tile.as_ptr()).value })
+    }
+    /// Moves the cursor one tile towards the bottom of the stack
+    /// Returns false, without moving, if the cursor is already past the bottom
+    pub fn move_next(&mut self) -> bool {
+        match self.current {
+            Some(tile) => {
+                self.above = Some(tile);
+                self.current = unsafe { (*tile.as_ptr()).next };
+                self.depth += 1;
+                true
+            }
+            None => false,
+        }
+    }
+    /// Returns the number of tiles above the cursor, 0 at the top of the stack
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+    /// Links a new tile with the data right below the cursor, which stays where it is
+    ///
+    /// Past the bottom of the stack there is nothing to link below,
+    /// so the data becomes the new bottom and the cursor rests on it
+    ///
+    /// Increases the size of stack by 1 unit
+    pub fn insert_after(&mut self, data: T) {
+        match self.current {
+            Some(tile) => {
+                let next = unsafe { (*tile.as_ptr()).next };
+                let new_tile = self.stack.pool.alloc(Tile { value: data, next });
+                unsafe { (*tile.as_ptr()).next = Some(new_tile) };
+                if next.is_none() {
+                    self.stack.tail = Some(new_tile);
+                }
+            }
+            None => {
+                let new_tile = self.stack.pool.alloc(Tile::new(data));
+                // Past the bottom, the tile above is the bottom one, if any
+                match self.above {
+                    Some(above) => unsafe { (*above.as_ptr()).next = Some(new_tile) },
+                    None => self.stack.head = Some(new_tile),
+                }
+                self.stack.tail = Some(new_tile);
+                self.current = Some(new_tile);
+            }
+        }
+        self.stack.size += 1;
+    }
+    /// Unlinks the tile under the cursor and returns the data it contains,
+    /// leaving the cursor on the tile that was below it
+    /// Returns None if the cursor is past the bottom of the stack
+    ///
+    /// Reduces the size of stack by 1 unit
+    pub fn remove_current(&mut self) -> Option<T> {
+        let tile = self.current?;
+        let next = unsafe { (*tile.as_ptr()).next.take() };
+        match self.above {
+            Some(above) => unsafe { (*above.as_ptr()).next = next },
+            None => self.stack.head = next,
+        }
+        if next.is_none() {
+            self.stack.tail = self.above;
+        }
+        self.current = next;
+        self.stack.size -= 1;
+        Some(unsafe { self.stack.pool.recycle(tile) })
+    }
+    /// Detaches every tile below the cursor and returns them as a new stack,
+    /// leaving the tile under the cursor at the bottom of this one
+    ///
+    /// Returns an empty stack if the cursor is on the bottom tile or past it
+    pub fn split_after(&mut self) -> Stack<T> {
+        let mut rest = Stack::new();
+        let current = match self.current {
+            Some(tile) => tile,
+            None => return rest,
+        };
+        rest.head = unsafe { (*current.as_ptr()).next.take() };
+        if rest.head.is_some() {
+            rest.tail = self.stack.tail.replace(current);
+            rest.size = self.stack.size - (self.depth + 1);
+            self.stack.size -= rest.size;
+        }
+        rest
+    }
+}
+
+// The cursor borrows the stack mutably, and hands out references to the data like it
+unsafe impl<T: Send> Send for CursorMut<'_, T> {}
+unsafe impl<T: Sync> Sync for CursorMut<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::Stack;
+
+    fn sample() -> Stack<u8> {
+        let mut stack = Stack::new();
+        for value in 1..=5 {
+            stack.push(value);
+        }
+        stack
+    }
+
+    #[test]
+    fn walk() {
+        let stack = sample();
+        let mut cursor = stack.cursor();
+        assert_eq!(Some(&5), cursor.peek());
+        assert_eq!(Some(&4), cursor.peek_next());
+        while cursor.peek() != Some(&2) {
+            assert!(cursor.move_next());
+        }
+        assert_eq!(3, cursor.depth());
+        let copy = cursor.clone();
+        assert!(cursor.move_next());
+        assert_eq!(None, cursor.peek_next());
+        assert!(cursor.move_next());
+        assert!(!cursor.move_next());
+        assert_eq!(None, cursor.peek());
+        assert_eq!(Some(&2), copy.peek());
+        assert_eq!(None, Stack::<u8>::new().cursor().peek());
+    }
+
+    #[test]
+    fn edit_at_depth() {
+        let mut stack = sample();
+        let mut cursor = stack.cursor_mut();
+        cursor.move_next();
+        *cursor.peek_mut().unwrap() *= 10;
+        cursor.move_next();
+        assert_eq!(Some(3), cursor.remove_current());
+        assert_eq!(Some(&2), cursor.peek());
+        cursor.insert_after(7);
+        assert_eq!(Some(&7), cursor.peek_next());
+        assert_eq!(2, cursor.depth());
+        assert_eq!(5, stack.size);
+        assert_eq!(vec![&5, &40, &2, &7, &1], stack.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn edit_past_bottom() {
+        let mut stack = Stack::new();
+        let mut cursor = stack.cursor_mut();
+        assert_eq!(None, cursor.remove_current());
+        cursor.insert_after(1);
+        assert_eq!(Some(&1), cursor.peek());
+        cursor.move_next();
+        cursor.insert_after(2);
+        assert_eq!(Some(2), cursor.remove_current());
+        assert_eq!(1, stack.size);
+        assert_eq!(Some(1), stack.pop());
+    }
+
+    #[test]
+    fn split() {
+        let mut stack = sample();
+        let mut cursor = stack.cursor_mut();
+        cursor.move_next();
+        let mut rest = cursor.split_after();
+        assert_eq!(0, cursor.split_after().size);
+        assert_eq!(2, stack.size);
+        assert_eq!(vec![&5, &4], stack.iter().collect::<Vec<_>>());
+        assert_eq!(3, rest.size);
+        assert_eq!(vec![&3, &2, &1], rest.iter().collect::<Vec<_>>());
+
+        let mut cursor = rest.cursor_mut();
+        while cursor.move_next() {}
+        assert_eq!(0, cursor.split_after().size);
+        assert_eq!(3, rest.size);
+    }
+
+    #[test]
+    fn keeps_tail() {
+        let mut stack = sample();
+        let mut cursor = stack.cursor_mut();
+        while cursor.peek() != Some(&1) {
+            cursor.move_next();
+        }
+        assert_eq!(Some(1), cursor.remove_current());
+        cursor.insert_after(0);
+        let mut rest = stack.cursor_mut();
+        rest.move_next();
+        let mut rest = rest.split_after();
+        rest.cursor_mut().insert_after(9);
+
+        let mut bottom = Stack::new();
+        bottom.push(10);
+        bottom.append(&mut rest);
+        bottom.append(&mut stack);
+        assert_eq!(
+            vec![&5, &4, &3, &9, &2, &0, &10],
+            bottom.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(7, bottom.size);
+    }
+}
@@ -0,0 +1,587 @@
+//! Work-stealing Deque Data Structure
+//!
+//! `Worker` implements the Chase-Lev deque: its owner thread pushes and pops at the top
+//! like a stack, while any number of `Stealer`s take the oldest elements from the bottom,
+//! which is how idle threads of a task scheduler pick up work from busy ones
+//!
+//! Top and bottom follow the rest of the crate, the top being the most recent element,
+//! which flips the naming of the Chase-Lev paper
+//!
+//! The elements live in a growable ring buffer, and replaced buffers are reclaimed
+//! through `crossbeam-epoch` once no stealer can still be reading them
+//!
+//! Example
+//! ```rust
+//! use stack::{Steal, Worker};
+//! use std::thread;
+//!
+//! let worker = Worker::<i32>::new();
+//! worker.push(1);
+//! worker.push(2);
+//! let stealer = worker.stealer();
+//! let thief = thread::spawn(move || stealer.steal());
+//! assert_eq!(Steal::Success(1), thief.join().unwrap());
+//! assert_eq!(Some(2), worker.pop());
+//! ```
+//!
+//! The model tests run under `loom`:
+//! `RUSTFLAGS="--cfg loom --cfg crossbeam_loom" cargo test --release --lib deque`
+
+use crossbeam_epoch::{self as epoch, Atomic, Owned};
+use std::cell::Cell;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem::{self, MaybeUninit};
+use std::ptr;
+
+#[cfg(loom)]
+use loom::sync::{
+    atomic::{fence, AtomicIsize, Ordering},
+    Arc,
+};
+#[cfg(not(loom))]
+use std::sync::{
+    atomic::{fence, AtomicIsize, Ordering},
+    Arc,
+};
+
+/// Capacity of a new buffer, kept tiny under loom so the models exercise growth
+#[cfg(not(loom))]
+const MIN_CAPACITY: usize = 64;
+#[cfg(loom)]
+const MIN_CAPACITY: usize = 2;
+
+/// Ring buffer of a power of two slots, indexed by positions that wrap around it
+struct Buffer<T> {
+    ptr: *mut T,
+    capacity: usize,
+}
+
+impl<T> Buffer<T> {
+    fn alloc(capacity: usize) -> Self {
+        let mut slots = Vec::<T>::with_capacity(capacity);
+        let ptr = slots.as_mut_ptr();
+        mem::forget(slots);
+        Buffer { ptr, capacity }
+    }
+
+    /// Frees the slots, without dropping anything left in them
+    unsafe fn dealloc(self) {
+        drop(Vec::from_raw_parts(self.ptr, 0, self.capacity));
+    }
+
+    unsafe fn at(&self, index: isize) -> *mut T {
+        self.ptr.offset(index & (self.capacity as isize - 1))
+    }
+
+    // Volatile, since a stealer can read a slot the owner is overwriting after the stealer
+    // lost its race, in which case the copy it read is forgotten without being used
+    unsafe fn write(&self, index: isize, data: T) {
+        ptr::write_volatile(self.at(index), data);
+    }
+
+    unsafe fn read(&self, index: isize) -> MaybeUninit<T> {
+        ptr::read_volatile(self.at(index).cast::<MaybeUninit<T>>())
+    }
+}
+
+impl<T> Clone for Buffer<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Buffer<T> {}
+
+/// State shared by a worker and its stealers
+/// The elements sit between bottom, where stealers take them, and top, where the owner works
+struct Inner<T> {
+    bottom: AtomicIsize,
+    top: AtomicIsize,
+    buffer: Atomic<Buffer<T>>,
+}
+
+/// Drops the elements left between bottom and top, then frees the buffer
+impl<T> Drop for Inner<T> {
+    fn drop(&mut self) {
+        let bottom = self.bottom.load(Ordering::Relaxed);
+        let top = self.top.load(Ordering::Relaxed);
+        // `&mut self` guarantees the worker and every stealer are gone
+        unsafe {
+            let buffer = self.buffer.load(Ordering::Relaxed, epoch::unprotected());
+            let mut index = bottom;
+            while index != top {
+                ptr::drop_in_place(buffer.deref().at(index));
+                index = index.wrapping_add(1);
+            }
+            buffer.into_owned().into_box().dealloc();
+        }
+    }
+}
+
+/// The result of a steal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Steal<T> {
+    /// The deque was empty
+    Empty,
+    /// The stolen data
+    Success(T),
+    /// The steal lost a race with another thread, and may succeed if tried again
+    Retry,
+}
+
+impl<T> Steal<T> {
+    /// Returns the stolen data, or None if the steal did not succeed
+    pub fn success(self) -> Option<T> {
+        match self {
+            Steal::Success(data) => Some(data),
+            _ => None,
+        }
+    }
+    /// Returns true if the deque was empty
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Steal::Empty)
+    }
+    /// Returns true if the steal should be tried again
+    pub fn is_retry(&self) -> bool {
+        matches!(self, Steal::Retry)
+    }
+}
+
+/// Owner handle of a work-stealing deque, pushing and popping at the top
+/// It has the shared state of the deque
+/// It has its own copy of the buffer, which only the owner ever replaces
+///
+/// A worker can be sent to another thread but not shared, since only one thread may own the top
+pub struct Worker<T> {
+    inner: Arc<Inner<T>>,
+    buffer: Cell<Buffer<T>>,
+    _not_sync: PhantomData<*mut ()>,
+}
+
+impl<T> Worker<T> {
+    /// Initialize a new empty deque, owned by the returned worker
+    pub fn new() -> Self {
+        let buffer = Buffer::alloc(MIN_CAPACITY);
+        Worker {
+            inner: Arc::new(Inner {
+                bottom: AtomicIsize::new(0),
+                top: AtomicIsize::new(0),
+                buffer: Atomic::new(buffer),
+            }),
+            buffer: Cell::new(buffer),
+            _not_sync: PhantomData,
+        }
+    }
+    /// Returns a new stealer of this deque
+    pub fn stealer(&self) -> Stealer<T> {
+        Stealer {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+    /// Pushes the data onto the top of the deque, growing its buffer when full
+    pub fn push(&self, data: T) {
+        let top = self.inner.top.load(Ordering::Relaxed);
+        let bottom = self.inner.bottom.load(Ordering::Acquire);
+        let mut buffer = self.buffer.get();
+        if top.wrapping_sub(bottom) >= buffer.capacity as isize {
+            self.resize(buffer.capacity * 2);
+            buffer = self.buffer.get();
+        }
+        unsafe { buffer.write(top, data) };
+        // Publishes the slot to the stealers that acquire the new top
+        self.inner.top.store(top.wrapping_add(1), Ordering::Release);
+    }
+    /// Pops the top off the deque and returns the data it contains
+    /// Returns None if the deque is empty, or a stealer took the last element first
+    pub fn pop(&self) -> Option<T> {
+        let top = self.inner.top.load(Ordering::Relaxed);
+        let bottom = self.inner.bottom.load(Ordering::Relaxed);
+        if top.wrapping_sub(bottom) <= 0 {
+            return None;
+        }
+        // Reserve the top slot before looking at what the stealers have taken
+        let top = top.wrapping_sub(1);
+        self.inner.top.store(top, Ordering::Relaxed);
+        fence(Ordering::SeqCst);
+        let bottom = self.inner.bottom.load(Ordering::Relaxed);
+        let len = top.wrapping_sub(bottom);
+        if len < 0 {
+            self.inner.top.store(top.wrapping_add(1), Ordering::Relaxed);
+            return None;
+        }
+        let mut data = Some(unsafe { self.buffer.get().read(top) });
+        if len == 0 {
+            // The last element, stealers may be racing for it through bottom
+            if self
+                .inner
+                .bottom
+                .compare_exchange(
+                    bottom,
+                    bottom.wrapping_add(1),
+                    Ordering::SeqCst,
+                    Ordering::Relaxed,
+                )
+                .is_err()
+            {
+                // A stealer won, the copy is forgotten so the data is not dropped twice
+                data = None;
+            }
+            self.inner.top.store(top.wrapping_add(1), Ordering::Relaxed);
+        }
+        data.map(|data| unsafe { data.assume_init() })
+    }
+    /// Returns the number of elements in the deque
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+    /// Returns true if the deque holds no elements
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Moves the elements into a buffer of `capacity` slots, retiring the current one
+    fn resize(&self, capacity: usize) {
+        let top = self.inner.top.load(Ordering::Relaxed);
+        let bottom = self.inner.bottom.load(Ordering::Relaxed);
+        let old = self.buffer.get();
+        let new = Buffer::alloc(capacity);
+        let mut index = bottom;
+        while index != top {
+            unsafe { ptr::copy_nonoverlapping(old.at(index), new.at(index), 1) };
+            index = index.wrapping_add(1);
+        }
+        let guard = epoch::pin();
+        self.buffer.set(new);
+        let old = self.inner.buffer.swap(
+            Owned::new(new).into_shared(&guard),
+            Ordering::Release,
+            &guard,
+        );
+        // Stealers still reading the old buffer hold a guard that delays this
+        unsafe { guard.defer_unchecked(move || old.into_owned().into_box().dealloc()) };
+    }
+}
+
+impl<T> Inner<T> {
+    fn len(&self) -> usize {
+        let top = self.top.load(Ordering::Relaxed);
+        let bottom = self.bottom.load(Ordering::Relaxed);
+        top.wrapping_sub(bottom).max(0) as usize
+    }
+}
+
+impl<T> Default for Worker<T> {
+    fn default() -> Self {
+        Worker::new()
+    }
+}
+
+impl<T> fmt::Debug for Worker<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Worker").field("len", &self.len()).finish()
+    }
+}
+
+// Values move to the stealing threads, and the worker itself can move to another thread
+unsafe impl<T: Send> Send for Worker<T> {}
+
+/// Stealing handle of a work-stealing deque, taking the oldest elements from the bottom
+///
+/// Stealers are cloned to hand one to every thread that may run out of work
+pub struct Stealer<T> {
+    inner: Arc<Inner<T>>,
+}
+
+impl<T> Stealer<T> {
+    /// Takes the oldest element of the deque
+    pub fn steal(&self) -> Steal<T> {
+        let bottom = self.inner.bottom.load(Ordering::Acquire);
+        fence(Ordering::SeqCst);
+        let guard = epoch::pin();
+        let top = self.inner.top.load(Ordering::Acquire);
+        if top.wrapping_sub(bottom) <= 0 {
+            return Steal::Empty;
+        }
+        let buffer = self.inner.buffer.load(Ordering::Acquire, &guard);
+        // The guard keeps the buffer alive even if the owner replaces it meanwhile
+        let data = unsafe { buffer.deref().read(bottom) };
+        if self.inner.buffer.load(Ordering::Acquire, &guard) != buffer
+            || self
+                .inner
+                .bottom
+                .compare_exchange(
+                    bottom,
+                    bottom.wrapping_add(1),
+                    Ordering::SeqCst,
+                    Ordering::Relaxed,
+                )
+                .is_err()
+        {
+            return Steal::Retry;
+        }
+        Steal::Success(unsafe { data.assume_init() })
+    }
+    /// Takes about half of the elements of the deque, oldest first, and pushes them onto `dest`
+    /// Returns how many elements were moved
+    pub fn steal_batch(&self, dest: &Worker<T>) -> Steal<usize> {
+        let mut bottom = self.inner.bottom.load(Ordering::Acquire);
+        fence(Ordering::SeqCst);
+        let guard = epoch::pin();
+        let top = self.inner.top.load(Ordering::Acquire);
+        let len = top.wrapping_sub(bottom);
+        if len <= 0 {
+            return Steal::Empty;
+        }
+        let batch = ((len + 1) / 2) as usize;
+        let buffer = self.inner.buffer.load(Ordering::Acquire, &guard);
+        let mut stolen = 0;
+        // One element at a time: the owner pops without touching bottom until the last element,
+        // so a single compare-and-swap over a range could take what it already popped
+        while stolen < batch {
+            if stolen > 0 {
+                fence(Ordering::SeqCst);
+                let top = self.inner.top.load(Ordering::Acquire);
+                if top.wrapping_sub(bottom) <= 0 {
+                    break;
+                }
+            }
+            let data = unsafe { buffer.deref().read(bottom) };
+            if self.inner.buffer.load(Ordering::Acquire, &guard) != buffer
+                || self
+                    .inner
+                    .bottom
+                    .compare_exchange(
+                        bottom,
+                        bottom.wrapping_add(1),
+                        Ordering::SeqCst,
+                        Ordering::Relaxed,
+                    )
+                    .is_err()
+            {
+                break;
+            }
+            dest.push(unsafe { data.assume_init() });
+            bottom = bottom.wrapping_add(1);
+            stolen += 1;
+        }
+        match stolen {
+            0 => Steal::Retry,
+            stolen => Steal::Success(stolen),
+        }
+    }
+    /// Returns the number of elements in the deque
+    ///
+    /// The owner and other stealers may change it at any time, so the result can be stale
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+    /// Returns true if the deque holds no elements
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Clone for Stealer<T> {
+    fn clone(&self) -> Self {
+        Stealer {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Stealer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Stealer").field("len", &self.len()).finish()
+    }
+}
+
+// Stealers only move values out of the deque, and their operations are all atomic
+unsafe impl<T: Send> Send for Stealer<T> {}
+unsafe impl<T: Send> Sync for Stealer<T> {}
+
+#[cfg(all(test, not(loom)))]
+mod tests {
+    use super::{Steal, Worker, MIN_CAPACITY};
+    use std::rc::Rc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn basics() {
+        let worker = Worker::<u8>::new();
+        let stealer = worker.stealer();
+        assert_eq!(Steal::Empty, stealer.steal());
+        worker.push(3);
+        worker.push(6);
+        worker.push(9);
+        assert_eq!(3, stealer.len());
+        assert_eq!(Some(9), worker.pop());
+        assert_eq!(Steal::Success(3), stealer.clone().steal());
+        assert_eq!(Some(6), worker.pop());
+        assert_eq!(None, worker.pop());
+        assert!(worker.is_empty());
+        assert_eq!(None, stealer.steal().success());
+    }
+
+    #[test]
+    fn grows_and_drops() {
+        let counter = Rc::new(());
+        let worker = Worker::new();
+        for _ in 0..MIN_CAPACITY * 4 + 1 {
+            worker.push(Rc::clone(&counter));
+        }
+        let stealer = worker.stealer();
+        for _ in 0..MIN_CAPACITY {
+            drop(stealer.steal().success().unwrap());
+            drop(worker.pop().unwrap());
+        }
+        assert_eq!(MIN_CAPACITY * 2 + 1, worker.len());
+        drop(worker);
+        assert_eq!(MIN_CAPACITY * 2 + 2, Rc::strong_count(&counter));
+        drop(stealer);
+        assert_eq!(1, Rc::strong_count(&counter));
+    }
+
+    #[test]
+    fn steal_batch() {
+        let worker = Worker::new();
+        for i in 1..=5 {
+            worker.push(i);
+        }
+        let thief = Worker::new();
+        assert_eq!(Steal::Success(3), worker.stealer().steal_batch(&thief));
+        assert_eq!(vec![Some(3), Some(2), Some(1), None], {
+            (0..4).map(|_| thief.pop()).collect::<Vec<_>>()
+        });
+        assert_eq!(2, worker.len());
+        assert_eq!(Steal::Empty, thief.stealer().steal_batch(&worker));
+    }
+
+    #[test]
+    fn many_stealers() {
+        const TOTAL: usize = 100_000;
+        let worker = Worker::new();
+        let sum = Arc::new(AtomicUsize::new(0));
+        let done = Arc::new(AtomicUsize::new(0));
+        let thieves: Vec<_> = (0..4)
+            .map(|t| {
+                let stealer = worker.stealer();
+                let sum = Arc::clone(&sum);
+                let done = Arc::clone(&done);
+                thread::spawn(move || {
+                    let local = Worker::new();
+                    let mut count = 0;
+                    while done.load(Ordering::Acquire) < TOTAL {
+                        let stolen = if t % 2 == 0 {
+                            stealer.steal().success()
+                        } else {
+                            stealer.steal_batch(&local);
+                            local.pop()
+                        };
+                        for value in stolen.into_iter().chain(std::iter::from_fn(|| local.pop())) {
+                            sum.fetch_add(value, Ordering::Relaxed);
+                            done.fetch_add(1, Ordering::AcqRel);
+                            count += 1;
+                        }
+                    }
+                    count
+                })
+            })
+            .collect();
+        for value in 1..=TOTAL {
+            worker.push(value);
+            if value % 3 == 0 {
+                if let Some(value) = worker.pop() {
+                    sum.fetch_add(value, Ordering::Relaxed);
+                    done.fetch_add(1, Ordering::AcqRel);
+                }
+            }
+        }
+        while let Some(value) = worker.pop() {
+            sum.fetch_add(value, Ordering::Relaxed);
+            done.fetch_add(1, Ordering::AcqRel);
+        }
+        for thief in thieves {
+            thief.join().unwrap();
+        }
+        assert_eq!(TOTAL * (TOTAL + 1) / 2, sum.load(Ordering::Relaxed));
+    }
+}
+
+#[cfg(all(test, loom))]
+mod loom_tests {
+    use super::{Steal, Worker};
+    use loom::thread;
+
+    fn model(f: impl Fn() + Sync + Send + 'static) {
+        let mut builder = loom::model::Builder::new();
+        builder.preemption_bound = Some(3);
+        builder.check(f);
+    }
+
+    fn drain(worker: &Worker<u32>) -> Vec<u32> {
+        std::iter::from_fn(|| worker.pop()).collect()
+    }
+
+    #[test]
+    fn pop_races_steal() {
+        // Both sides go for the last element, exactly one may get it
+        model(|| {
+            let worker = Worker::new();
+            worker.push(1);
+            let stealer = worker.stealer();
+            let handle = thread::spawn(move || stealer.steal().success());
+            let mine = worker.pop();
+            let theirs = handle.join().unwrap();
+            assert_eq!(1, mine.into_iter().chain(theirs).count());
+        });
+    }
+
+    #[test]
+    fn steal_while_growing() {
+        // The pushes outgrow the initial buffer while a stealer reads from it
+        model(|| {
+            let worker = Worker::new();
+            worker.push(1);
+            worker.push(2);
+            let stealer = worker.stealer();
+            let handle = thread::spawn(move || loop {
+                match stealer.steal() {
+                    Steal::Retry => thread::yield_now(),
+                    steal => return steal.success(),
+                }
+            });
+            worker.push(3);
+            let theirs = handle.join().unwrap();
+            let mut all: Vec<u32> = theirs.into_iter().chain(drain(&worker)).collect();
+            assert_eq!(Some(1), theirs);
+            all.sort();
+            assert_eq!(vec![1, 2, 3], all);
+        });
+    }
+
+    #[test]
+    fn steal_batch_races_pop() {
+        model(|| {
+            let worker = Worker::new();
+            worker.push(1);
+            worker.push(2);
+            worker.push(3);
+            let stealer = worker.stealer();
+            let handle = thread::spawn(move || {
+                let local = Worker::new();
+                stealer.steal_batch(&local);
+                drain(&local)
+            });
+            let mut all = vec![worker.pop().unwrap()];
+            all.extend(worker.pop());
+            let theirs = handle.join().unwrap();
+            all.extend(theirs);
+            all.extend(drain(&worker));
+            all.sort();
+            assert_eq!(vec![1, 2, 3], all);
+        });
+    }
+}
@@ -0,0 +1,55 @@
+//! Errors shared by the fixed-capacity and closable stacks
+
+use core::fmt;
+
+/// Error returned by a push onto a full stack, carrying the rejected data
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Full<T>(pub T);
+
+impl<T> Full<T> {
+    /// Returns the data that could not be pushed
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> fmt::Debug for Full<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Full").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Display for Full<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("push onto a full stack")
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> std::error::Error for Full<T> {}
+
+/// Error returned by a push onto a closed stack, carrying the rejected data
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Closed<T>(pub T);
+
+impl<T> Closed<T> {
+    /// Returns the data that could not be pushed
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> fmt::Debug for Closed<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Closed").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Display for Closed<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("push onto a closed stack")
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> std::error::Error for Closed<T> {}
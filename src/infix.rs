@@ -0,0 +1,476 @@
+//! Infix to Postfix Conversion
+//!
+//! `Parser` runs Dijkstra's shunting-yard algorithm: operands go straight to the output,
+//! while operators, parentheses and function calls wait on a `Stack` until precedence
+//! and associativity say they can follow their operands
+//!
+//! The postfix output displays as an expression the `rpn` evaluator understands, with
+//! prefix operators renamed (e.g. unary `-` to `neg`) and each function call emitted
+//! after its arguments
+//!
+//! Example
+//! ```rust
+//! use stack::infix::{to_rpn, Parser};
+//! use stack::rpn::Evaluator;
+//!
+//! let postfix = Parser::arithmetic().parse("3 + 4 * 2 / (1 - 5) ^ 2 ^ 3").unwrap();
+//! assert_eq!("3 4 2 * 1 5 - 2 3 ^ ^ / +", to_rpn(&postfix));
+//!
+//! let postfix = Parser::arithmetic().parse("-(2 + 3) * 4").unwrap();
+//! let floats = Evaluator::floats().operator("neg", 1, |args: &[f64]| Ok(-args[0]));
+//! assert_eq!(Ok(-20.0), floats.evaluate(&to_rpn(&postfix)));
+//! ```
+
+use crate::Stack;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Which side binds first when operators of the same precedence meet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Assoc {
+    /// `a - b - c` is `(a - b) - c`
+    Left,
+    /// `a ^ b ^ c` is `a ^ (b ^ c)`
+    Right,
+}
+
+/// Error produced while converting an expression
+/// Positions are byte offsets into the expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InfixError {
+    /// The expression has no tokens
+    Empty,
+    /// A character that starts no operand, operator or parenthesis
+    UnknownSymbol { position: usize },
+    /// A token that cannot follow the previous one, e.g. two operands in a row
+    UnexpectedToken { position: usize },
+    /// The expression ended where an operand was expected
+    UnexpectedEnd { position: usize },
+    /// An opening parenthesis that is never closed
+    UnclosedParen { position: usize },
+    /// A closing parenthesis without a matching opening one
+    UnmatchedParen { position: usize },
+    /// A comma outside the parentheses of a function call
+    MisplacedComma { position: usize },
+}
+
+impl fmt::Display for InfixError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InfixError::Empty => write!(f, "empty expression"),
+            InfixError::UnknownSymbol { position } => {
+                write!(f, "unknown symbol at position {}", position)
+            }
+            InfixError::UnexpectedToken { position } => {
+                write!(f, "unexpected token at position {}", position)
+            }
+            InfixError::UnexpectedEnd { position } => {
+                write!(f, "expected an operand at position {}", position)
+            }
+            InfixError::UnclosedParen { position } => {
+                write!(f, "parenthesis at position {} is never closed", position)
+            }
+            InfixError::UnmatchedParen { position } => {
+                write!(f, "parenthesis at position {} closes nothing", position)
+            }
+            InfixError::MisplacedComma { position } => {
+                write!(f, "comma outside a function call at position {}", position)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InfixError {}
+
+/// A token of the postfix output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Postfix {
+    /// A number or identifier, copied from the expression
+    Operand(String),
+    /// An operator with the number of operands it takes
+    Operator { symbol: String, arity: usize },
+    /// A function call with the number of arguments it was given
+    Function { name: String, args: usize },
+}
+
+impl fmt::Display for Postfix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Postfix::Operand(text) => f.write_str(text),
+            Postfix::Operator { symbol, .. } => f.write_str(symbol),
+            Postfix::Function { name, .. } => f.write_str(name),
+        }
+    }
+}
+
+/// Joins the postfix tokens with spaces, ready for `rpn::Evaluator`
+pub fn to_rpn(postfix: &[Postfix]) -> String {
+    postfix
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[derive(Debug, Clone)]
+struct Binary {
+    precedence: u8,
+    assoc: Assoc,
+}
+
+#[derive(Debug, Clone)]
+struct Unary {
+    precedence: u8,
+    output: String,
+}
+
+/// Converts infix expressions to postfix
+/// It has a table of binary operators, with their precedence and associativity
+/// It has a table of prefix unary operators, with their precedence and output symbol
+#[derive(Debug, Clone, Default)]
+pub struct Parser {
+    binary: BTreeMap<String, Binary>,
+    unary: BTreeMap<String, Unary>,
+}
+
+/// An entry of the operator stack
+enum Pending {
+    Operator {
+        symbol: String,
+        precedence: u8,
+        arity: usize,
+    },
+    Paren {
+        position: usize,
+        function: Option<String>,
+        args: usize,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lexeme<'a> {
+    Word(&'a str),
+    Symbol(&'a str),
+    Open,
+    Close,
+    Comma,
+}
+
+impl Parser {
+    /// Initialize a new parser with no operators
+    pub fn new() -> Self {
+        Parser::default()
+    }
+    /// Initialize a new parser with `+ -` below `* / %` below unary `-` (as `neg`) below `^`,
+    /// all left associative except `^`
+    pub fn arithmetic() -> Self {
+        Parser::new()
+            .binary("+", 1, Assoc::Left)
+            .binary("-", 1, Assoc::Left)
+            .binary("*", 2, Assoc::Left)
+            .binary("/", 2, Assoc::Left)
+            .binary("%", 2, Assoc::Left)
+            .unary("-", 3, "neg")
+            .binary("^", 4, Assoc::Right)
+    }
+    /// Adds a binary operator, a higher precedence binds tighter
+    pub fn binary(mut self, symbol: &str, precedence: u8, assoc: Assoc) -> Self {
+        self.binary
+            .insert(symbol.to_string(), Binary { precedence, assoc });
+        self
+    }
+    /// Adds a prefix unary operator, written to the output as `output`
+    ///
+    /// A symbol can be both unary and binary, the position of the token decides
+    pub fn unary(mut self, symbol: &str, precedence: u8, output: &str) -> Self {
+        self.unary.insert(
+            symbol.to_string(),
+            Unary {
+                precedence,
+                output: output.to_string(),
+            },
+        );
+        self
+    }
+    /// Converts the infix expression to postfix
+    ///
+    /// Operands are runs of letters, digits, `_` and `.`, and an operand directly
+    /// followed by `(` is a function call whose arguments are separated by commas
+    pub fn parse(&self, expr: &str) -> Result<Vec<Postfix>, InfixError> {
+        let lexemes = self.tokenize(expr)?;
+        if lexemes.is_empty() {
+            return Err(InfixError::Empty);
+        }
+        let mut output = Vec::new();
+        let mut pending = Stack::new();
+        let mut expect_operand = true;
+        let mut i = 0;
+        while i < lexemes.len() {
+            let (position, lexeme) = lexemes[i];
+            i += 1;
+            match lexeme {
+                Lexeme::Word(word) => {
+                    if !expect_operand {
+                        return Err(InfixError::UnexpectedToken { position });
+                    }
+                    if let Some(&(_, Lexeme::Open)) = lexemes.get(i) {
+                        i += 1;
+                        pending.push(Pending::Paren {
+                            position,
+                            function: Some(word.to_string()),
+                            args: 0,
+                        });
+                        if let Some(&(_, Lexeme::Close)) = lexemes.get(i) {
+                            i += 1;
+                            pending.pop();
+                            output.push(Postfix::Function {
+                                name: word.to_string(),
+                                args: 0,
+                            });
+                            expect_operand = false;
+                        }
+                    } else {
+                        output.push(Postfix::Operand(word.to_string()));
+                        expect_operand = false;
+                    }
+                }
+                Lexeme::Symbol(symbol) if expect_operand => {
+                    let unary = self
+                        .unary
+                        .get(symbol)
+                        .ok_or(InfixError::UnexpectedToken { position })?;
+                    // A prefix operator has no left operand, so nothing is popped for it
+                    pending.push(Pending::Operator {
+                        symbol: unary.output.clone(),
+                        precedence: unary.precedence,
+                        arity: 1,
+                    });
+                }
+                Lexeme::Symbol(symbol) => {
+                    let binary = self
+                        .binary
+                        .get(symbol)
+                        .ok_or(InfixError::UnexpectedToken { position })?;
+                    while let Some(Pending::Operator { precedence, .. }) = pending.peek() {
+                        let pops = *precedence > binary.precedence
+                            || (*precedence == binary.precedence && binary.assoc == Assoc::Left);
+                        if !pops {
+                            break;
+                        }
+                        output.push(pop_operator(&mut pending));
+                    }
+                    pending.push(Pending::Operator {
+                        symbol: symbol.to_string(),
+                        precedence: binary.precedence,
+                        arity: 2,
+                    });
+                    expect_operand = true;
+                }
+                Lexeme::Open => {
+                    if !expect_operand {
+                        return Err(InfixError::UnexpectedToken { position });
+                    }
+                    pending.push(Pending::Paren {
+                        position,
+                        function: None,
+                        args: 0,
+                    });
+                }
+                Lexeme::Comma => {
+                    if expect_operand {
+                        return Err(InfixError::UnexpectedToken { position });
+                    }
+                    flush_operators(&mut pending, &mut output);
+                    match pending.peek_mut() {
+                        Some(Pending::Paren {
+                            function: Some(_),
+                            args,
+                            ..
+                        }) => *args += 1,
+                        _ => return Err(InfixError::MisplacedComma { position }),
+                    }
+                    expect_operand = true;
+                }
+                Lexeme::Close => {
+                    if expect_operand {
+                        return Err(InfixError::UnexpectedToken { position });
+                    }
+                    flush_operators(&mut pending, &mut output);
+                    match pending.pop() {
+                        Some(Pending::Paren {
+                            function: Some(name),
+                            args,
+                            ..
+                        }) => output.push(Postfix::Function {
+                            name,
+                            args: args + 1,
+                        }),
+                        Some(Pending::Paren { function: None, .. }) => {}
+                        _ => return Err(InfixError::UnmatchedParen { position }),
+                    }
+                }
+            }
+        }
+        if expect_operand {
+            return Err(InfixError::UnexpectedEnd {
+                position: expr.len(),
+            });
+        }
+        flush_operators(&mut pending, &mut output);
+        if let Some(Pending::Paren { position, .. }) = pending.peek() {
+            return Err(InfixError::UnclosedParen {
+                position: *position,
+            });
+        }
+        Ok(output)
+    }
+
+    fn tokenize<'a>(&self, expr: &'a str) -> Result<Vec<(usize, Lexeme<'a>)>, InfixError> {
+        let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '.';
+        let mut lexemes = Vec::new();
+        let mut rest = expr.char_indices().peekable();
+        while let Some((position, c)) = rest.next() {
+            let lexeme = match c {
+                c if c.is_whitespace() => continue,
+                '(' => Lexeme::Open,
+                ')' => Lexeme::Close,
+                ',' => Lexeme::Comma,
+                c if is_word(c) => {
+                    let mut end = position + c.len_utf8();
+                    while let Some(&(i, c)) = rest.peek() {
+                        if !is_word(c) {
+                            break;
+                        }
+                        end = i + c.len_utf8();
+                        rest.next();
+                    }
+                    Lexeme::Word(&expr[position..end])
+                }
+                _ => {
+                    // Longest operator symbol starting here
+                    let symbol = self
+                        .binary
+                        .keys()
+                        .chain(self.unary.keys())
+                        .filter(|symbol| expr[position..].starts_with(symbol.as_str()))
+                        .max_by_key(|symbol| symbol.len())
+                        .ok_or(InfixError::UnknownSymbol { position })?;
+                    let end = position + symbol.len();
+                    while rest.peek().is_some_and(|&(i, _)| i < end) {
+                        rest.next();
+                    }
+                    Lexeme::Symbol(&expr[position..end])
+                }
+            };
+            lexemes.push((position, lexeme));
+        }
+        Ok(lexemes)
+    }
+}
+
+fn pop_operator(pending: &mut Stack<Pending>) -> Postfix {
+    match pending.pop() {
+        Some(Pending::Operator { symbol, arity, .. }) => Postfix::Operator { symbol, arity },
+        _ => unreachable!("only called with an operator on top"),
+    }
+}
+
+/// Moves operators to the output until the stack is empty or a parenthesis is on top
+fn flush_operators(pending: &mut Stack<Pending>, output: &mut Vec<Postfix>) {
+    while let Some(Pending::Operator { .. }) = pending.peek() {
+        output.push(pop_operator(pending));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{to_rpn, Assoc, InfixError, Parser, Postfix};
+
+    fn rpn(expr: &str) -> String {
+        to_rpn(&Parser::arithmetic().parse(expr).unwrap())
+    }
+
+    #[test]
+    fn precedence_and_associativity() {
+        assert_eq!("1 2 3 * +", rpn("1 + 2 * 3"));
+        assert_eq!("1 2 + 3 *", rpn("(1 + 2) * 3"));
+        assert_eq!("1 2 - 3 -", rpn("1 - 2 - 3"));
+        assert_eq!("2 3 2 ^ ^", rpn("2 ^ 3 ^ 2"));
+        assert_eq!("a b c * d / -", rpn("a - b*c/d"));
+    }
+
+    #[test]
+    fn unary() {
+        assert_eq!("2 neg", rpn("-2"));
+        assert_eq!("2 2 ^ neg", rpn("-2 ^ 2"));
+        assert_eq!("1 2 neg neg -", rpn("1 - --2"));
+        assert_eq!("2 neg 3 *", rpn("-2 * 3"));
+    }
+
+    #[test]
+    fn functions() {
+        let postfix = Parser::arithmetic()
+            .parse("max(1, 2 + 3, f(x), g())")
+            .unwrap();
+        assert_eq!("1 2 3 + x f g max", to_rpn(&postfix));
+        assert_eq!(
+            Some(&Postfix::Function {
+                name: "max".to_string(),
+                args: 4
+            }),
+            postfix.last()
+        );
+        assert!(postfix.contains(&Postfix::Function {
+            name: "g".to_string(),
+            args: 0
+        }));
+    }
+
+    #[test]
+    fn custom_operators() {
+        let parser = Parser::new()
+            .binary("||", 1, Assoc::Left)
+            .binary("&&", 2, Assoc::Left)
+            .binary("=", 0, Assoc::Right)
+            .unary("!", 3, "not");
+        let postfix = parser.parse("x = a || !b && c").unwrap();
+        assert_eq!("x a b not c && || =", to_rpn(&postfix));
+    }
+
+    #[test]
+    fn errors() {
+        let parser = Parser::arithmetic();
+        assert_eq!(Err(InfixError::Empty), parser.parse(" "));
+        assert_eq!(
+            Err(InfixError::UnclosedParen { position: 4 }),
+            parser.parse("1 + (2 * 3")
+        );
+        assert_eq!(
+            Err(InfixError::UnmatchedParen { position: 5 }),
+            parser.parse("1 + 2) * 3")
+        );
+        assert_eq!(
+            Err(InfixError::MisplacedComma { position: 2 }),
+            parser.parse("1 , 2")
+        );
+        assert_eq!(
+            Err(InfixError::UnexpectedToken { position: 2 }),
+            parser.parse("1 2")
+        );
+        assert_eq!(
+            Err(InfixError::UnexpectedEnd { position: 3 }),
+            parser.parse("1 +")
+        );
+        assert_eq!(
+            Err(InfixError::UnknownSymbol { position: 2 }),
+            parser.parse("1 $ 2")
+        );
+        assert_eq!(
+            Err(InfixError::UnexpectedToken { position: 0 }),
+            parser.parse("* 2")
+        );
+    }
+}
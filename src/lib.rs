@@ -1,23 +1,169 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use checkpoint::Journal;
+#[cfg(feature = "alloc")]
+use core::cmp::Ordering;
+#[cfg(feature = "alloc")]
+use core::fmt;
+#[cfg(feature = "alloc")]
+use core::hash::{Hash, Hasher};
+#[cfg(feature = "alloc")]
+use core::iter::{FromIterator, FusedIterator};
+#[cfg(feature = "alloc")]
+use core::marker::PhantomData;
+#[cfg(feature = "alloc")]
+use core::mem;
+#[cfg(feature = "alloc")]
+use core::ptr::NonNull;
+#[cfg(feature = "alloc")]
+use pool::Pool;
+
+#[cfg(feature = "alloc")]
+pub mod aggregate;
+pub mod array;
+#[cfg(feature = "async")]
+pub mod asynchronous;
+#[cfg(feature = "std")]
+pub mod blocking;
+#[cfg(feature = "alloc")]
+pub mod bounded;
+#[cfg(feature = "alloc")]
+pub mod checkpoint;
+#[cfg(feature = "concurrent")]
+pub mod concurrent;
+#[cfg(feature = "alloc")]
+pub mod cursor;
+#[cfg(feature = "concurrent")]
+pub mod deque;
+mod error;
+#[cfg(feature = "alloc")]
+pub mod infix;
+pub mod lifo;
+#[cfg(feature = "alloc")]
+pub mod minmax;
+#[cfg(feature = "alloc")]
+pub mod persistent;
+#[cfg(feature = "alloc")]
+mod pool;
+#[cfg(feature = "alloc")]
+pub mod rpn;
+#[cfg(all(feature = "serde", feature = "alloc"))]
+pub mod serialize;
+pub mod slice;
+#[cfg(feature = "alloc")]
+pub mod undo;
+#[cfg(feature = "alloc")]
+pub mod vec;
+
+#[cfg(feature = "alloc")]
+pub use aggregate::{AggregateQueue, AggregateStack, Monoid};
+pub use array::ArrayStack;
+#[cfg(feature = "async")]
+pub use asynchronous::AsyncStack;
+#[cfg(feature = "std")]
+pub use blocking::BlockingStack;
+#[cfg(feature = "std")]
+pub use bounded::SyncBoundedStack;
+#[cfg(feature = "alloc")]
+pub use bounded::{BoundedStack, Overflow};
+#[cfg(feature = "alloc")]
+pub use checkpoint::Checkpoint;
+#[cfg(feature = "concurrent")]
+pub use concurrent::ConcurrentStack;
+#[cfg(feature = "alloc")]
+pub use cursor::{Cursor, CursorMut};
+#[cfg(feature = "concurrent")]
+pub use deque::{Steal, Stealer, Worker};
+pub use error::{Closed, Full};
+pub use lifo::Lifo;
+#[cfg(feature = "alloc")]
+pub use minmax::MinMaxStack;
+#[cfg(feature = "alloc")]
+pub use persistent::{ArcPersistentStack, PersistentStack};
+pub use slice::SliceStack;
+#[cfg(feature = "alloc")]
+pub use undo::UndoHistory;
+#[cfg(feature = "alloc")]
+pub use vec::VecStack;
+
 /// Stack Data Structure
 /// It has a head, that points to the top of the stack
+/// It has a tail, that points to the bottom of the stack so it can be appended in O(1)
 /// It has a size, updated on every push and pop
-#[derive(Debug)]
+/// It has a pool, keeping the allocations of popped tiles for later pushes
+/// It has a journal, recording pushes and pops while a checkpoint is open
+///
+/// The pool and the journal live out of line, so a stack using neither pays a pointer for each
+///
+/// The stack owns its tiles through raw links, like `std::collections::LinkedList`,
+/// so the tail stays valid however the chain above it is relinked
+///
+/// The head is only reachable inside the crate since version 0.2, because relinking it
+/// from outside would leave the tail dangling; `peek`, `iter` and the cursors replace it
+#[cfg(feature = "alloc")]
 pub struct Stack<T> {
-    pub head: Option<Box<Tile<T>>>,
+    pub(crate) head: Option<NonNull<Tile<T>>>,
+    tail: Option<NonNull<Tile<T>>>,
     pub size: usize,
+    pool: Pool<T>,
+    journal: Option<Box<Journal<T>>>,
+    _owns: PhantomData<Box<Tile<T>>>,
 }
 
+// Every link points to a tile the stack owns, as if through a `Box`
+#[cfg(feature = "alloc")]
+unsafe impl<T: Send> Send for Stack<T> {}
+#[cfg(feature = "alloc")]
+unsafe impl<T: Sync> Sync for Stack<T> {}
+
+#[cfg(feature = "alloc")]
 impl<T> Stack<T> {
     /// Initialize a new stack with its head pointing to None and with zero size
     ///
     /// Example
     /// ```rust
+    /// # use stack::Stack;
     /// let new_stack = Stack::<i32>::new(); // Stack<i32> { head: None, size: 0 }
     /// ```
     pub fn new() -> Self {
         Stack {
             head: None,
+            tail: None,
+            size: 0,
+            pool: Pool::new(0),
+            journal: None,
+            _owns: PhantomData,
+        }
+    }
+    /// Initialize a new empty stack that recycles up to `limit` popped tiles,
+    /// so pushes following pops reuse their allocations instead of calling the allocator
+    ///
+    /// Example
+    /// ```rust
+    /// # use stack::Stack;
+    /// let mut new_stack = Stack::<i32>::pooled(64);
+    /// new_stack.push(5);
+    /// new_stack.pop();
+    /// assert_eq!(1, new_stack.pooled_tiles());
+    /// new_stack.push(6); // reuses the popped tile
+    /// assert_eq!(0, new_stack.pooled_tiles());
+    /// ```
+    pub fn pooled(limit: usize) -> Self {
+        Stack {
+            head: None,
+            tail: None,
             size: 0,
+            pool: Pool::new(limit),
+            journal: None,
+            _owns: PhantomData,
         }
     }
     /// Get a reference to data in the head of the Stack
@@ -25,13 +171,29 @@ impl<T> Stack<T> {
     ///
     /// Example
     /// ```rust
+    /// # use stack::Stack;
+    /// # let mut new_stack = Stack::<i32>::new();
     /// let head_data: Option<&i32> = new_stack.peek();
     /// ```
     pub fn peek(&self) -> Option<&T> {
-        match self.head {
-            Some(ref v) => Some(&v.as_ref().value),
-            None => None,
-        }
+        // Every link points to a live tile owned by the stack
+        self.head.map(|tile| unsafe { &(*tile.as_ptr()).value })
+    }
+    /// Get a mutable reference to data in the head of the Stack
+    /// Returns None if the stack is empty
+    ///
+    /// Example
+    /// ```rust
+    /// # use stack::Stack;
+    /// # let mut new_stack = Stack::<i32>::new();
+    /// # new_stack.push(5);
+    /// if let Some(head_data) = new_stack.peek_mut() {
+    ///     *head_data += 1;
+    /// }
+    /// # assert_eq!(Some(&6), new_stack.peek());
+    /// ```
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        self.head.map(|tile| unsafe { &mut (*tile.as_ptr()).value })
     }
     /// Pops the top off the stack and returns the data it contains
     /// Returns None if the stack is empty
@@ -40,14 +202,24 @@ impl<T> Stack<T> {
     ///
     /// Example
     /// ```rust
+    /// # use stack::Stack;
+    /// # let mut new_stack = Stack::<i32>::new();
     /// let top_data: Option<i32> = new_stack.pop();
     /// ```
     pub fn pop(&mut self) -> Option<T> {
-        match self.head.take() {
-            Some(v) => {
-                self.head = v.next;
+        match self.head {
+            Some(tile) => {
+                // The tile is unlinked before it is recycled
+                self.head = unsafe { (*tile.as_ptr()).next.take() };
+                if self.head.is_none() {
+                    self.tail = None;
+                }
                 self.size -= 1;
-                Some(v.value)
+                let data = unsafe { self.pool.recycle(tile) };
+                if let Some(journal) = self.journal.as_mut() {
+                    journal.popped(&data);
+                }
+                Some(data)
             }
             None => None,
         }
@@ -58,62 +230,845 @@ impl<T> Stack<T> {
     ///
     /// Example
     /// ```rust
+    /// # use stack::Stack;
+    /// # let mut new_stack = Stack::<i32>::new();
     /// new_stack.push(5);
     /// ```
     pub fn push(&mut self, data: T) {
         self.size += 1;
-        match self.head.take() {
-            Some(v) => {
-                self.head = Some(Box::new(Tile {
-                    value: data,
-                    next: Some(v),
-                }));
+        let tile = self.pool.alloc(Tile {
+            value: data,
+            next: self.head,
+        });
+        if self.head.is_none() {
+            self.tail = Some(tile);
+        }
+        self.head = Some(tile);
+        if let Some(journal) = self.journal.as_mut() {
+            journal.pushed();
+        }
+    }
+    /// Moves every tile of `other` on top of this stack, leaving `other` empty [O(1) operation]
+    ///
+    /// The top of `other` becomes the top of the stack, and the sizes of both stacks are updated
+    ///
+    /// # Panics
+    ///
+    /// Panics if a checkpoint is open on either stack, as a rollback could not undo it
+    ///
+    /// Example
+    /// ```rust
+    /// # use stack::Stack;
+    /// let mut main = Stack::<i32>::new();
+    /// main.push(1);
+    /// let mut scratch = Stack::<i32>::new();
+    /// scratch.push(2);
+    /// scratch.push(3);
+    /// main.append(&mut scratch);
+    /// assert_eq!(vec![&3, &2, &1], main.iter().collect::<Vec<_>>());
+    /// assert_eq!(0, scratch.size);
+    /// ```
+    pub fn append(&mut self, other: &mut Stack<T>) {
+        self.assert_no_checkpoint("append");
+        other.assert_no_checkpoint("append");
+        let other_tail = match other.tail.take() {
+            Some(tail) => tail,
+            None => return,
+        };
+        // The tail is the bottom tile of `other`, its `next` is None
+        unsafe { (*other_tail.as_ptr()).next = self.head };
+        self.head = other.head.take();
+        if self.tail.is_none() {
+            self.tail = Some(other_tail);
+        }
+        self.size += mem::replace(&mut other.size, 0);
+    }
+    /// Splits the stack in two, returning the top `depth` tiles as a new stack [O(depth) operation]
+    ///
+    /// The stack keeps the tiles below, and the sizes of both stacks are updated
+    ///
+    /// # Panics
+    ///
+    /// Panics if `depth` is greater than the size of the stack, or if a checkpoint is open
+    ///
+    /// Example
+    /// ```rust
+    /// # use stack::Stack;
+    /// # let mut new_stack = Stack::<i32>::new();
+    /// # for i in 1..=4 { new_stack.push(i); }
+    /// let top = new_stack.split_off(1);
+    /// assert_eq!(vec![&4], top.iter().collect::<Vec<_>>());
+    /// assert_eq!(vec![&3, &2, &1], new_stack.iter().collect::<Vec<_>>());
+    /// ```
+    pub fn split_off(&mut self, depth: usize) -> Stack<T> {
+        self.assert_no_checkpoint("split off");
+        assert!(
+            depth <= self.size,
+            "split depth {} is greater than the size {}",
+            depth,
+            self.size
+        );
+        let mut top = Stack::new();
+        if depth == 0 {
+            return top;
+        }
+        let mut last = self.head.unwrap();
+        for _ in 1..depth {
+            last = unsafe { (*last.as_ptr()).next.unwrap() };
+        }
+        let rest = unsafe { (*last.as_ptr()).next.take() };
+        top.tail = Some(last);
+        top.head = mem::replace(&mut self.head, rest);
+        top.size = depth;
+        if self.head.is_none() {
+            self.tail = None;
+        }
+        self.size -= depth;
+        top
+    }
+    /// Removes all the tiles from the stack
+    ///
+    /// Resets the size of stack to 0
+    pub fn clear(&mut self) {
+        while self.pop().is_some() {}
+    }
+    /// Keeps only the data for which the predicate returns true, visiting it from the top
+    /// of the stack to the bottom [O(n) operation]
+    ///
+    /// The kept tiles are relinked in place, in the same order, and the removed ones are recycled
+    ///
+    /// Example
+    /// ```rust
+    /// # use stack::Stack;
+    /// let mut my_stack = Stack::from(vec![1, 2, 3, 4]);
+    /// my_stack.retain(|x| x % 2 == 0);
+    /// assert_eq!(vec![&4, &2], my_stack.iter().collect::<Vec<_>>());
+    /// assert_eq!(2, my_stack.size);
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.retain_mut(|value| f(value));
+    }
+    /// Keeps only the data for which the predicate returns true, letting the predicate
+    /// modify it, visiting it from the top of the stack to the bottom [O(n) operation]
+    ///
+    /// If the predicate panics, the data it has not returned for yet stays in the stack
+    ///
+    /// # Panics
+    ///
+    /// Panics if a checkpoint is open, as a rollback could not undo it
+    pub fn retain_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        self.assert_no_checkpoint("retain");
+        let mut above: Option<NonNull<Tile<T>>> = None;
+        let mut current = self.head;
+        while let Some(tile) = current {
+            // The chain stays whole while the predicate runs, so a panic leaves it consistent
+            let keep = f(unsafe { &mut (*tile.as_ptr()).value });
+            current = unsafe { (*tile.as_ptr()).next };
+            if keep {
+                above = Some(tile);
+                continue;
             }
-            None => {
-                self.head = Some(Box::new(Tile {
-                    value: data,
-                    next: None,
-                }));
+            match above {
+                Some(above) => unsafe { (*above.as_ptr()).next = current },
+                None => self.head = current,
+            }
+            if current.is_none() {
+                self.tail = above;
+            }
+            self.size -= 1;
+            unsafe {
+                (*tile.as_ptr()).next = None;
+                self.pool.recycle(tile);
+            }
+        }
+    }
+    /// Removes consecutive data for which `same_bucket` returns true, keeping the topmost
+    /// of each run [O(n) operation]
+    ///
+    /// `same_bucket` gets the data below first, then the kept data above it
+    ///
+    /// # Panics
+    ///
+    /// Panics if a checkpoint is open, as a rollback could not undo it
+    pub fn dedup_by<F>(&mut self, mut same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        self.assert_no_checkpoint("dedup");
+        let mut kept = match self.head {
+            Some(tile) => tile,
+            None => return,
+        };
+        while let Some(next) = unsafe { (*kept.as_ptr()).next } {
+            let duplicate =
+                unsafe { same_bucket(&mut (*next.as_ptr()).value, &mut (*kept.as_ptr()).value) };
+            if !duplicate {
+                kept = next;
+                continue;
+            }
+            let below = unsafe { (*next.as_ptr()).next.take() };
+            unsafe { (*kept.as_ptr()).next = below };
+            if below.is_none() {
+                self.tail = Some(kept);
+            }
+            self.size -= 1;
+            unsafe { self.pool.recycle(next) };
+        }
+    }
+    /// Removes consecutive data with equal keys, keeping the topmost of each run [O(n) operation]
+    ///
+    /// Example
+    /// ```rust
+    /// # use stack::Stack;
+    /// let mut my_stack = Stack::from(vec![10, 11, 20, 12]);
+    /// my_stack.dedup_by_key(|x| *x / 10);
+    /// assert_eq!(vec![&12, &20, &11], my_stack.iter().collect::<Vec<_>>());
+    /// ```
+    pub fn dedup_by_key<K, F>(&mut self, mut key: F)
+    where
+        K: PartialEq,
+        F: FnMut(&mut T) -> K,
+    {
+        self.dedup_by(|below, above| key(below) == key(above));
+    }
+    /// Reverses the order of the stack in place, so the bottom becomes the top [O(n) operation]
+    ///
+    /// # Panics
+    ///
+    /// Panics if a checkpoint is open, as a rollback could not undo it
+    ///
+    /// Example
+    /// ```rust
+    /// # use stack::Stack;
+    /// let mut my_stack = Stack::from(vec![1, 2, 3]);
+    /// my_stack.reverse();
+    /// assert_eq!(Some(&1), my_stack.peek());
+    /// ```
+    pub fn reverse(&mut self) {
+        self.assert_no_checkpoint("reverse");
+        let mut rest = self.head.take();
+        // The old top ends up at the bottom
+        self.tail = rest;
+        while let Some(tile) = rest {
+            rest = unsafe { mem::replace(&mut (*tile.as_ptr()).next, self.head) };
+            self.head = Some(tile);
+        }
+    }
+    /// Returns the number of popped tiles kept for reuse
+    pub fn pooled_tiles(&self) -> usize {
+        self.pool.len()
+    }
+    /// Returns the maximum number of popped tiles kept for reuse
+    pub fn pool_limit(&self) -> usize {
+        self.pool.limit()
+    }
+    /// Changes the maximum number of popped tiles kept for reuse,
+    /// freeing the tiles above the new limit
+    ///
+    /// A limit of 0 turns pooling off, which is how `Stack::new` starts
+    pub fn set_pool_limit(&mut self, limit: usize) {
+        self.pool.set_limit(limit);
+    }
+    /// Frees every tile kept for reuse, without changing the limit
+    pub fn shrink(&mut self) {
+        self.pool.shrink();
+    }
+    /// Returns an iterator over references to the data, from the top of the stack to the bottom
+    ///
+    /// Example
+    /// ```rust
+    /// # use stack::Stack;
+    /// # let mut new_stack = Stack::<i32>::new();
+    /// # new_stack.push(1);
+    /// # new_stack.push(2);
+    /// let top_to_bottom: Vec<&i32> = new_stack.iter().collect(); // [&2, &1]
+    /// # assert_eq!(vec![&2, &1], top_to_bottom);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head,
+            len: self.size,
+            _marker: PhantomData,
+        }
+    }
+    /// Returns an iterator over mutable references to the data, from the top of the stack to the bottom
+    ///
+    /// Example
+    /// ```rust
+    /// # use stack::Stack;
+    /// # let mut new_stack = Stack::<i32>::new();
+    /// # new_stack.push(1);
+    /// for data in new_stack.iter_mut() {
+    ///     *data *= 10;
+    /// }
+    /// # assert_eq!(Some(&10), new_stack.peek());
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            next: self.head,
+            len: self.size,
+            _marker: PhantomData,
+        }
+    }
+    /// Searches for the first data, from the top, for which the predicate returns true [O(n) operation]
+    /// Returns its position counted from the bottom like `search`, or None if there is no match
+    ///
+    /// Example
+    /// ```rust
+    /// # use stack::Stack;
+    /// let my_stack = Stack::from(vec![4, 7, 10]);
+    /// assert_eq!(Some(2), my_stack.search_by(|x| x % 2 == 1));
+    /// ```
+    pub fn search_by<F>(&self, f: F) -> Option<usize>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.iter().position(f).map(|depth| self.size - depth)
+    }
+    /// Searches for the first data, from the top, whose key extracted by `f` equals `key` [O(n) operation]
+    /// Returns its position counted from the bottom like `search`, or None if there is no match
+    ///
+    /// Example
+    /// ```rust
+    /// # use stack::Stack;
+    /// let my_stack = Stack::from(vec![("a", 1), ("b", 2)]);
+    /// assert_eq!(Some(1), my_stack.search_by_key(&"a", |&(name, _)| name));
+    /// ```
+    pub fn search_by_key<K, F>(&self, key: &K, mut f: F) -> Option<usize>
+    where
+        K: PartialEq,
+        F: FnMut(&T) -> K,
+    {
+        self.search_by(|value| f(value) == *key)
+    }
+    /// Get a reference to the first data, from the top, for which the predicate returns true
+    /// Returns None if there is no match [O(n) operation]
+    pub fn find<F>(&self, mut f: F) -> Option<&T>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.iter().find(|value| f(value))
+    }
+    /// Get a mutable reference to the first data, from the top, for which the predicate returns true
+    /// Returns None if there is no match [O(n) operation]
+    ///
+    /// Example
+    /// ```rust
+    /// # use stack::Stack;
+    /// let mut my_stack = Stack::from(vec![1, 2, 3]);
+    /// if let Some(even) = my_stack.find_mut(|x| x % 2 == 0) {
+    ///     *even = 20;
+    /// }
+    /// assert_eq!(vec![&3, &20, &1], my_stack.iter().collect::<Vec<_>>());
+    /// ```
+    pub fn find_mut<F>(&mut self, mut f: F) -> Option<&mut T>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.iter_mut().find(|value| f(value))
+    }
+    /// Returns a cursor on the top of the stack, that can walk down the tiles
+    pub fn cursor(&self) -> Cursor<'_, T> {
+        Cursor::new(self)
+    }
+    /// Returns a cursor on the top of the stack, that can walk down the tiles and edit them in place
+    ///
+    /// # Panics
+    ///
+    /// Panics if a checkpoint is open, as a rollback could not undo it
+    ///
+    /// Example
+    /// ```rust
+    /// # use stack::Stack;
+    /// # let mut new_stack = Stack::<i32>::new();
+    /// # new_stack.push(1);
+    /// # new_stack.push(2);
+    /// let mut cursor = new_stack.cursor_mut();
+    /// cursor.move_next();
+    /// assert_eq!(Some(1), cursor.remove_current());
+    /// # assert_eq!(1, new_stack.size);
+    /// ```
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T> {
+        self.assert_no_checkpoint("edit through a cursor");
+        CursorMut::new(self)
+    }
+
+    /// Links a new tile with the data below the bottom of the stack [O(1) operation]
+    #[cfg(any(feature = "serde", test))]
+    pub(crate) fn push_bottom(&mut self, data: T) {
+        let tile = self.pool.alloc(Tile::new(data));
+        match self.tail {
+            // The tail is the bottom tile of this stack, its `next` is None
+            Some(tail) => unsafe { (*tail.as_ptr()).next = Some(tile) },
+            None => self.head = Some(tile),
+        }
+        self.tail = Some(tile);
+        self.size += 1;
+    }
+    /// Unlinks the bottom tile and returns the data it contains [O(n) operation]
+    #[cfg(test)]
+    pub(crate) fn pop_bottom(&mut self) -> Option<T> {
+        let mut above = None;
+        let mut bottom = self.head?;
+        while let Some(next) = unsafe { (*bottom.as_ptr()).next } {
+            above = Some(bottom);
+            bottom = next;
+        }
+        match above {
+            Some(above) => unsafe { (*above.as_ptr()).next = None },
+            None => self.head = None,
+        }
+        self.tail = above;
+        self.size -= 1;
+        Some(unsafe { self.pool.recycle(bottom) })
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Default for Stack<T> {
+    fn default() -> Self {
+        Stack::new()
+    }
+}
+
+/// Frees the tiles one at a time, so dropping a deep stack does not
+/// recurse through the whole chain and overflow the call stack
+#[cfg(feature = "alloc")]
+impl<T> Drop for Stack<T> {
+    fn drop(&mut self) {
+        while let Some(tile) = self.head {
+            // Every tile came from a `Box` leaked by the pool, and is unlinked before it is freed
+            unsafe {
+                self.head = (*tile.as_ptr()).next;
+                drop(Box::from_raw(tile.as_ptr()));
             }
         }
     }
 }
 
+/// Formats the data as a list, from the top of the stack to the bottom
+#[cfg(feature = "alloc")]
+impl<T: fmt::Debug> fmt::Debug for Stack<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self).finish()
+    }
+}
+
+/// Clones the data from the top of the stack to the bottom, linking each new tile below the
+/// last one so the clone keeps the same order without recursing through the chain [O(n) operation]
+///
+/// The clone has the same pool limit with an empty pool, and no open checkpoint
+#[cfg(feature = "alloc")]
+impl<T: Clone> Clone for Stack<T> {
+    fn clone(&self) -> Self {
+        let mut stack = Stack::pooled(self.pool_limit());
+        let mut link = &mut stack.head;
+        for data in self {
+            let tile = stack.pool.alloc(Tile::new(data.clone()));
+            *link = Some(tile);
+            // The new tile is owned by the clone, and its `next` is the next link to fill
+            link = unsafe { &mut (*tile.as_ptr()).next };
+            stack.tail = Some(tile);
+            stack.size += 1;
+        }
+        stack
+    }
+}
+
+/// Two stacks are equal if they hold equal data in the same order, compared from the top
+#[cfg(feature = "alloc")]
+impl<T: PartialEq> PartialEq for Stack<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.size == other.size && self.iter().eq(other)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Eq> Eq for Stack<T> {}
+
+/// Hashes the size, then the data from the top of the stack to the bottom
+#[cfg(feature = "alloc")]
+impl<T: Hash> Hash for Stack<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.size);
+        for data in self {
+            data.hash(state);
+        }
+    }
+}
+
+/// Stacks are ordered lexicographically from the top to the bottom,
+/// so a stack sorts before every stack it is the top part of
+///
+/// Example
+/// ```rust
+/// # use stack::Stack;
+/// let low = Stack::from(vec![9, 1]); // 1 on top
+/// let high = Stack::from(vec![0, 2]); // 2 on top
+/// assert!(low < high);
+/// ```
+#[cfg(feature = "alloc")]
+impl<T: PartialOrd> PartialOrd for Stack<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Ord> Ord for Stack<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other)
+    }
+}
+
+/// Pushes the data in the order the iterator yields it, so the last item ends up on top
+#[cfg(feature = "alloc")]
+impl<T> Extend<T> for Stack<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for data in iter {
+            self.push(data);
+        }
+    }
+}
+
+/// Pushes copies of the data in the order the iterator yields it, so the last item ends up on top
+#[cfg(feature = "alloc")]
+impl<'a, T: Copy + 'a> Extend<&'a T> for Stack<T> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+/// Pushes the data in the order the iterator yields it, so the last item ends up on top
+///
+/// Collecting a stack's own iterator therefore reverses it
+///
+/// Example
+/// ```rust
+/// # use stack::Stack;
+/// let my_stack: Stack<i32> = (1..=3).collect();
+/// assert_eq!(Some(&3), my_stack.peek());
+/// let reversed: Stack<i32> = my_stack.into_iter().collect();
+/// assert_eq!(Some(&1), reversed.peek());
+/// ```
+#[cfg(feature = "alloc")]
+impl<T> FromIterator<T> for Stack<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut stack = Stack::new();
+        stack.extend(iter);
+        stack
+    }
+}
+
+/// Pushes the elements from the first to the last, so the last element of the vector,
+/// the top of a vector used as a stack, is the top of the stack
+#[cfg(feature = "alloc")]
+impl<T> From<Vec<T>> for Stack<T> {
+    fn from(vec: Vec<T>) -> Self {
+        vec.into_iter().collect()
+    }
+}
+
+/// Pushes the elements from the first to the last, so the last element is the top of the stack
+#[cfg(feature = "alloc")]
+impl<T, const N: usize> From<[T; N]> for Stack<T> {
+    fn from(array: [T; N]) -> Self {
+        IntoIterator::into_iter(array).collect()
+    }
+}
+
+#[cfg(feature = "alloc")]
 impl<T: PartialEq> Stack<T> {
     /// Searches for data in the whole stack [O(n) operation]
     /// Returns None is stack is empty
     ///
     /// Example
     /// ```rust
+    /// # use stack::Stack;
+    /// # let mut new_stack = Stack::<i32>::new();
     /// let result: Option<usize> = new_stack.search(3);
     /// ```
     pub fn search(&self, data: T) -> Option<usize> {
-        let mut ptr = self.head.as_ref();
-        let mut pos = self.size;
-        while let Some(ref p) = ptr {
-            if p.as_ref().value == data {
-                return Some(pos);
-            }
-            ptr = p.next.as_ref();
-            pos -= 1;
-        }
-        return None;
+        self.search_by(|value| *value == data)
+    }
+    /// Removes consecutive equal data, keeping the topmost of each run [O(n) operation]
+    ///
+    /// Example
+    /// ```rust
+    /// # use stack::Stack;
+    /// let mut my_stack = Stack::from(vec![1, 1, 2, 2, 2, 1]);
+    /// my_stack.dedup();
+    /// assert_eq!(vec![&1, &2, &1], my_stack.iter().collect::<Vec<_>>());
+    /// ```
+    pub fn dedup(&mut self) {
+        self.dedup_by(|below, above| below == above);
+    }
+    /// Returns true if the stack holds data equal to `data` [O(n) operation]
+    pub fn contains(&self, data: &T) -> bool {
+        self.iter().any(|value| value == data)
+    }
+    /// Searches for data from the top of the stack [O(n) operation]
+    /// Returns the number of tiles above the first match, so the top is at 0
+    /// like the depth of a cursor, or None if there is no match
+    ///
+    /// Example
+    /// ```rust
+    /// # use stack::Stack;
+    /// let my_stack = Stack::from(vec![7, 8, 9]); // 9 on top
+    /// assert_eq!(Some(1), my_stack.position_from_top(&8));
+    /// assert_eq!(Some(2), my_stack.search(8));
+    /// ```
+    pub fn position_from_top(&self, data: &T) -> Option<usize> {
+        self.iter().position(|value| value == data)
+    }
+    /// Searches for every match of data in the whole stack [O(n) operation]
+    /// Returns their positions counted from the bottom like `search`, from the top match down
+    ///
+    /// Example
+    /// ```rust
+    /// # use stack::Stack;
+    /// let my_stack = Stack::from(vec![1, 2, 1, 3]);
+    /// assert_eq!(vec![3, 1], my_stack.search_all(&1));
+    /// ```
+    pub fn search_all(&self, data: &T) -> Vec<usize> {
+        let size = self.size;
+        self.iter()
+            .enumerate()
+            .filter(|(_, value)| *value == data)
+            .map(|(depth, _)| size - depth)
+            .collect()
     }
 }
 
-#[derive(Debug)]
+/// A tile of a Stack, holding one element and a link to the tile below it
+///
+/// A tile does not own the tile below it: the stack owns them all, so a tile compares,
+/// hashes, orders and clones by its data alone
+#[cfg(feature = "alloc")]
 pub struct Tile<T> {
     value: T,
-    next: Option<Box<Tile<T>>>,
+    next: Option<NonNull<Tile<T>>>,
 }
 
+#[cfg(feature = "alloc")]
 impl<T> Tile<T> {
     pub fn new(value: T) -> Self {
         Tile { value, next: None }
     }
 }
 
-#[cfg(test)]
+#[cfg(feature = "alloc")]
+impl<T: fmt::Debug> fmt::Debug for Tile<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tile")
+            .field("value", &self.value)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> From<T> for Tile<T> {
+    fn from(value: T) -> Self {
+        Tile::new(value)
+    }
+}
+
+/// Clones the data into a new tile, linked to nothing
+#[cfg(feature = "alloc")]
+impl<T: Clone> Clone for Tile<T> {
+    fn clone(&self) -> Self {
+        Tile::new(self.value.clone())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: PartialEq> PartialEq for Tile<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Eq> Eq for Tile<T> {}
+
+#[cfg(feature = "alloc")]
+impl<T: Hash> Hash for Tile<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: PartialOrd> PartialOrd for Tile<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Ord> Ord for Tile<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value.cmp(&other.value)
+    }
+}
+
+// A tile is sent or shared along with its data, its link is only followed by the stack
+#[cfg(feature = "alloc")]
+unsafe impl<T: Send> Send for Tile<T> {}
+#[cfg(feature = "alloc")]
+unsafe impl<T: Sync> Sync for Tile<T> {}
+
+/// Borrowing iterator over a Stack, created by `Stack::iter`
+/// Yields the data from the top of the stack to the bottom
+#[cfg(feature = "alloc")]
+pub struct Iter<'a, T> {
+    next: Option<NonNull<Tile<T>>>,
+    len: usize,
+    _marker: PhantomData<&'a Tile<T>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.map(|tile| {
+            // The stack is borrowed for 'a, so its tiles stay live and unchanged
+            let tile = unsafe { &*tile.as_ptr() };
+            self.next = tile.next;
+            self.len -= 1;
+            &tile.value
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+// The iterator only hands out shared references to the data
+#[cfg(feature = "alloc")]
+unsafe impl<T: Sync> Send for Iter<'_, T> {}
+#[cfg(feature = "alloc")]
+unsafe impl<T: Sync> Sync for Iter<'_, T> {}
+
+#[cfg(feature = "alloc")]
+impl<T> FusedIterator for Iter<'_, T> {}
+
+#[cfg(feature = "alloc")]
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Iter {
+            next: self.next,
+            len: self.len,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Mutably borrowing iterator over a Stack, created by `Stack::iter_mut`
+/// Yields the data from the top of the stack to the bottom
+#[cfg(feature = "alloc")]
+pub struct IterMut<'a, T> {
+    next: Option<NonNull<Tile<T>>>,
+    len: usize,
+    _marker: PhantomData<&'a mut Tile<T>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next.map(|tile| {
+            // The stack is borrowed mutably for 'a, and each tile is yielded once
+            self.next = unsafe { (*tile.as_ptr()).next };
+            self.len -= 1;
+            unsafe { &mut (*tile.as_ptr()).value }
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+// The iterator hands out mutable references to the data, like `&mut T`
+#[cfg(feature = "alloc")]
+unsafe impl<T: Send> Send for IterMut<'_, T> {}
+#[cfg(feature = "alloc")]
+unsafe impl<T: Sync> Sync for IterMut<'_, T> {}
+
+#[cfg(feature = "alloc")]
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+/// Owning iterator over a Stack, created by `Stack::into_iter`
+/// Pops the data from the top of the stack to the bottom
+#[cfg(feature = "alloc")]
+pub struct IntoIter<T>(Stack<T>);
+
+#[cfg(feature = "alloc")]
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.pop()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.size, Some(self.0.size))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+#[cfg(feature = "alloc")]
+impl<T> FusedIterator for IntoIter<T> {}
+
+#[cfg(feature = "alloc")]
+impl<T> IntoIterator for Stack<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> IntoIterator for &'a Stack<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> IntoIterator for &'a mut Stack<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
 mod tests {
     use super::Stack;
     #[test]
@@ -129,6 +1084,278 @@ mod tests {
         assert_eq!(2, stack.size);
         assert_eq!(Some(6), stack.pop());
         assert_eq!(Some(&3), stack.peek());
-        assert_eq!(3, stack.head.unwrap().as_ref().value);
+    }
+
+    #[test]
+    fn search_family() {
+        let mut stack = Stack::from(vec![
+            "a".to_string(),
+            "bb".to_string(),
+            "a".to_string(),
+            "ccc".to_string(),
+        ]);
+        let a = "a".to_string();
+        assert_eq!(Some(3), stack.search(a.clone()));
+        assert!(stack.contains(&a));
+        assert!(!stack.contains(&"d".to_string()));
+        assert_eq!(Some(1), stack.position_from_top(&a));
+        assert_eq!(vec![3, 1], stack.search_all(&a));
+        assert!(stack.search_all(&"d".to_string()).is_empty());
+        assert_eq!(Some(4), stack.search_by(|s| s.len() > 1));
+        assert_eq!(Some(2), stack.search_by_key(&2, |s| s.len()));
+        assert_eq!(None, stack.search_by_key(&0, |s| s.len()));
+        assert_eq!(Some(&"bb".to_string()), stack.find(|s| s.starts_with('b')));
+        stack.find_mut(|s| *s == a).unwrap().push('!');
+        assert_eq!(Some(3), stack.search("a!".to_string()));
+        assert_eq!(None, stack.find_mut(|s| s.is_empty()));
+    }
+
+    #[test]
+    fn retain_dedup_reverse() {
+        let mut stack = Stack::<u8>::pooled(8);
+        stack.extend(vec![1, 2, 2, 3, 4, 4, 4, 5]);
+        let kept = stack.iter().nth(1).unwrap() as *const u8;
+        stack.retain(|x| *x != 5 && *x != 1);
+        assert_eq!(
+            vec![&4, &4, &4, &3, &2, &2],
+            stack.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(kept, stack.iter().next().unwrap() as *const u8);
+        assert_eq!(6, stack.size);
+        assert_eq!(2, stack.pooled_tiles());
+        // The tail follows the removed bottom
+        stack.push_bottom(0);
+        assert_eq!(Some(0), stack.pop_bottom());
+
+        stack.dedup();
+        assert_eq!(vec![&4, &3, &2], stack.iter().collect::<Vec<_>>());
+        assert_eq!(3, stack.size);
+        stack.push_bottom(1);
+        assert_eq!(4, stack.size);
+
+        stack.retain_mut(|x| {
+            *x *= 10;
+            *x > 20
+        });
+        assert_eq!(vec![&40, &30], stack.iter().collect::<Vec<_>>());
+        stack.push_bottom(31);
+        stack.dedup_by_key(|x| *x / 10);
+        assert_eq!(vec![&40, &30], stack.iter().collect::<Vec<_>>());
+        stack.push_bottom(7);
+
+        let top = stack.iter().next().unwrap() as *const u8;
+        stack.reverse();
+        assert_eq!(vec![&7, &30, &40], stack.iter().collect::<Vec<_>>());
+        assert_eq!(top, stack.iter().last().unwrap() as *const u8);
+        stack.push_bottom(50);
+        assert_eq!(Some(50), stack.pop_bottom());
+
+        stack.retain(|_| false);
+        assert_eq!(0, stack.size);
+        assert_eq!(None, stack.pop_bottom());
+        stack.push_bottom(1);
+        stack.reverse();
+        stack.dedup();
+        assert_eq!(vec![&1], stack.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn retain_dedup_survive_panics() {
+        use std::panic::{self, AssertUnwindSafe};
+
+        let mut stack = Stack::from(vec![1, 2, 3, 4, 5]);
+        let unwound = panic::catch_unwind(AssertUnwindSafe(|| {
+            stack.retain(|x| match x {
+                2 => panic!("predicate bug"),
+                x => x % 2 == 1,
+            })
+        }));
+        assert!(unwound.is_err());
+        // 4 was removed before the panic, the tiles below the panic are all still linked
+        assert_eq!(vec![&5, &3, &2, &1], stack.iter().collect::<Vec<_>>());
+        assert_eq!(4, stack.size);
+        stack.push_bottom(0);
+        assert_eq!(Some(0), stack.pop_bottom());
+
+        let mut stack = Stack::from(vec![1, 1, 2, 2]);
+        let unwound = panic::catch_unwind(AssertUnwindSafe(|| {
+            stack.dedup_by(|below, _| match below {
+                1 => panic!("same_bucket bug"),
+                _ => true,
+            })
+        }));
+        assert!(unwound.is_err());
+        assert_eq!(vec![&2, &1, &1], stack.iter().collect::<Vec<_>>());
+        assert_eq!(3, stack.size);
+        stack.push_bottom(0);
+        assert_eq!(vec![&2, &1, &1, &0], stack.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn iterators() {
+        let mut stack = Stack::<u8>::new();
+        stack.push(1);
+        stack.push(2);
+        stack.push(3);
+
+        let mut iter = stack.iter();
+        assert_eq!((3, Some(3)), iter.size_hint());
+        assert_eq!(Some(&3), iter.next());
+        assert_eq!(2, iter.len());
+        assert_eq!(vec![&2, &1], iter.collect::<Vec<_>>());
+
+        for data in &mut stack {
+            *data *= 2;
+        }
+        assert_eq!(vec![&6, &4, &2], (&stack).into_iter().collect::<Vec<_>>());
+
+        let mut into_iter = stack.into_iter();
+        assert_eq!(Some(6), into_iter.next());
+        assert_eq!(2, into_iter.len());
+        assert_eq!(vec![4, 2], into_iter.by_ref().collect::<Vec<_>>());
+        assert_eq!(None, into_iter.next());
+    }
+
+    #[test]
+    fn pooled() {
+        let mut stack = Stack::<String>::pooled(2);
+        for word in &["a", "b", "c"] {
+            stack.push(word.to_string());
+        }
+        let top = stack.head;
+        assert_eq!(Some("c".to_string()), stack.pop());
+        assert_eq!(1, stack.pooled_tiles());
+        stack.push("d".to_string());
+        assert_eq!(top, stack.head);
+        assert_eq!(0, stack.pooled_tiles());
+
+        stack.clear();
+        assert_eq!(0, stack.size);
+        assert_eq!(2, stack.pooled_tiles());
+        stack.set_pool_limit(1);
+        assert_eq!(1, stack.pooled_tiles());
+        stack.shrink();
+        assert_eq!(0, stack.pooled_tiles());
+        assert_eq!(1, stack.pool_limit());
+        stack.push("e".to_string());
+        assert_eq!(Some(&"e".to_string()), stack.peek());
+        stack.set_pool_limit(0);
+        assert_eq!(0, stack.pool_limit());
+        assert_eq!(Some("e".to_string()), stack.pop());
+        assert_eq!(0, stack.pooled_tiles());
+
+        // Head, tail and size, plus one pointer each for the unused pool and journal
+        assert_eq!(
+            5 * std::mem::size_of::<usize>(),
+            std::mem::size_of::<Stack<u8>>()
+        );
+    }
+
+    #[test]
+    fn append_split_off() {
+        let mut stack = Stack::<u8>::new();
+        let mut other = Stack::<u8>::new();
+        stack.append(&mut other);
+        assert_eq!(0, stack.size);
+        other.push(1);
+        other.push(2);
+        stack.append(&mut other);
+        assert_eq!(0, other.size);
+        assert_eq!(None, other.pop());
+
+        let mut top = stack.split_off(1);
+        assert_eq!(vec![&2], top.iter().collect::<Vec<_>>());
+        assert_eq!(vec![&1], stack.iter().collect::<Vec<_>>());
+        top.push(3);
+        stack.append(&mut top);
+        top.push(4);
+        top.append(&mut stack);
+        stack.append(&mut top);
+        assert_eq!(4, stack.size);
+        assert_eq!(vec![&3, &2, &1, &4], stack.iter().collect::<Vec<_>>());
+
+        let mut all = stack.split_off(4);
+        assert_eq!(0, stack.size);
+        assert_eq!(0, all.split_off(0).size);
+        stack.push(5);
+        stack.append(&mut all);
+        assert_eq!(5, stack.size);
+        assert_eq!(Some(5), stack.pop_bottom());
+        stack.append(&mut Stack::new());
+        let mut bottom = Stack::new();
+        bottom.push(6);
+        bottom.append(&mut stack);
+        assert_eq!(vec![&3, &2, &1, &4, &6], bottom.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    #[should_panic(expected = "greater than the size")]
+    fn split_off_too_deep() {
+        Stack::<u8>::new().split_off(1);
+    }
+
+    #[test]
+    fn std_traits() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        fn hash(stack: &Stack<u8>) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            stack.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let mut stack = Stack::from([1, 2]);
+        stack.extend(vec![3]);
+        stack.extend(&[4]);
+        assert_eq!(vec![&4, &3, &2, &1], stack.iter().collect::<Vec<_>>());
+        assert_eq!(stack, Stack::from(vec![1, 2, 3, 4]));
+        assert_eq!(stack, (1..=4).collect());
+
+        let mut clone = stack.clone();
+        assert_eq!(stack, clone);
+        assert_eq!(hash(&stack), hash(&clone));
+        // The clone has its own tail, so appending it leaves the original untouched
+        let mut below = Stack::from([0]);
+        below.append(&mut clone.clone());
+        below.push_bottom(9);
+        assert_eq!(
+            vec![&4, &3, &2, &1, &0, &9],
+            below.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(4, stack.size);
+        assert_eq!(Some(1), clone.pop_bottom());
+        clone.push_bottom(1);
+        assert_eq!(stack, clone);
+        clone.push(0);
+        assert_ne!(stack, clone);
+        assert!(stack > clone);
+        assert!(Stack::from([3, 4]) < stack);
+        assert!(stack.split_off(2) > stack);
+        assert!(Stack::new() < stack);
+        assert_eq!(Stack::default(), Stack::<u8>::new());
+
+        let tile = super::Tile::from(5);
+        assert_eq!(tile, tile.clone());
+        assert!(tile < super::Tile::new(6));
+    }
+
+    #[test]
+    fn clone_and_compare_deep_stack() {
+        let stack: Stack<u32> = (0..1_000_000).collect();
+        let mut clone = stack.clone();
+        assert_eq!(stack, clone);
+        *clone.iter_mut().last().unwrap() = 1;
+        assert!(stack < clone);
+    }
+
+    #[test]
+    fn drop_deep_stack() {
+        let mut stack = Stack::<u32>::new();
+        for i in 0..3_000_000 {
+            stack.push(i);
+        }
+        assert_eq!(3_000_000, stack.size);
+        drop(stack);
     }
 }
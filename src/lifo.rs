@@ -0,0 +1,311 @@
+//! Last-in first-out collections
+//!
+//! `Lifo` captures the operations of a stack whose top can be edited in place,
+//! so algorithms written against it accept `Stack`, `VecStack`, `ArrayStack` and `SliceStack`
+//! as well as the standard library collections
+//!
+//! Pushing through `Lifo` cannot fail: the fixed-capacity `ArrayStack` and `SliceStack`
+//! panic when pushed onto while full, as their own `push` does for `ArrayStack`
+//!
+//! The other stacks of the crate do not implement it: `MinMaxStack` and `AggregateStack`
+//! cannot hand out a mutable top without breaking their summaries, `BoundedStack` applies
+//! an overflow policy instead of panicking, and the concurrent and persistent stacks push
+//! through a shared reference
+//!
+//! Example
+//! ```rust
+//! # #[cfg(feature = "alloc")] {
+//! use stack::{Lifo, Stack};
+//!
+//! fn reverse<S: Lifo<char>>(mut scratch: S, text: &str) -> String {
+//!     for c in text.chars() {
+//!         scratch.push(c);
+//!     }
+//!     let mut reversed = String::new();
+//!     while let Some(c) = scratch.pop() {
+//!         reversed.push(c);
+//!     }
+//!     reversed
+//! }
+//!
+//! assert_eq!("cba", reverse(Stack::new(), "abc"));
+//! assert_eq!("cba", reverse(Vec::new(), "abc"));
+//! # }
+//! ```
+
+use crate::{ArrayStack, SliceStack};
+#[cfg(feature = "alloc")]
+use crate::{Stack, VecStack};
+#[cfg(feature = "alloc")]
+use alloc::collections::{LinkedList, VecDeque};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// A collection that hands its elements back in the reverse order they were pushed
+pub trait Lifo<T> {
+    /// Pushes the data onto the top of the collection
+    fn push(&mut self, data: T);
+    /// Removes the top of the collection and returns it
+    /// Returns None if the collection is empty
+    fn pop(&mut self) -> Option<T>;
+    /// Get a reference to the top of the collection
+    /// Returns None if the collection is empty
+    fn peek(&self) -> Option<&T>;
+    /// Get a mutable reference to the top of the collection
+    /// Returns None if the collection is empty
+    fn peek_mut(&mut self) -> Option<&mut T>;
+    /// Returns the number of elements in the collection
+    fn len(&self) -> usize;
+    /// Returns true if the collection holds no elements
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Removes all the elements from the collection
+    fn clear(&mut self);
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Lifo<T> for Stack<T> {
+    fn push(&mut self, data: T) {
+        Stack::push(self, data)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        Stack::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        Stack::peek(self)
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut T> {
+        Stack::peek_mut(self)
+    }
+
+    fn len(&self) -> usize {
+        self.size
+    }
+
+    fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    fn clear(&mut self) {
+        Stack::clear(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Lifo<T> for VecStack<T> {
+    fn push(&mut self, data: T) {
+        VecStack::push(self, data)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        VecStack::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        VecStack::peek(self)
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut T> {
+        VecStack::peek_mut(self)
+    }
+
+    fn len(&self) -> usize {
+        self.size
+    }
+
+    fn clear(&mut self) {
+        VecStack::clear(self)
+    }
+}
+
+/// Pushing onto a full stack panics, like `ArrayStack::push`
+impl<T, const N: usize> Lifo<T> for ArrayStack<T, N> {
+    fn push(&mut self, data: T) {
+        ArrayStack::push(self, data)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        ArrayStack::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        ArrayStack::peek(self)
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut T> {
+        ArrayStack::peek_mut(self)
+    }
+
+    fn len(&self) -> usize {
+        self.size()
+    }
+
+    fn clear(&mut self) {
+        ArrayStack::clear(self)
+    }
+}
+
+/// Pushing onto a full stack panics, like `ArrayStack::push`
+impl<T> Lifo<T> for SliceStack<'_, T> {
+    fn push(&mut self, data: T) {
+        if SliceStack::push(self, data).is_err() {
+            panic!(
+                "push onto a full SliceStack of capacity {}",
+                self.capacity()
+            );
+        }
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        SliceStack::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        SliceStack::peek(self)
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut T> {
+        SliceStack::peek_mut(self)
+    }
+
+    fn len(&self) -> usize {
+        self.size()
+    }
+
+    fn clear(&mut self) {
+        SliceStack::clear(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// The top of the stack is the end of the `Vec`
+impl<T> Lifo<T> for Vec<T> {
+    fn push(&mut self, data: T) {
+        Vec::push(self, data)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        Vec::pop(self)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.last()
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut T> {
+        self.last_mut()
+    }
+
+    fn len(&self) -> usize {
+        Vec::len(self)
+    }
+
+    fn clear(&mut self) {
+        Vec::clear(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// The top of the stack is the back of the `VecDeque`
+impl<T> Lifo<T> for VecDeque<T> {
+    fn push(&mut self, data: T) {
+        self.push_back(data)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop_back()
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.back()
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut T> {
+        self.back_mut()
+    }
+
+    fn len(&self) -> usize {
+        VecDeque::len(self)
+    }
+
+    fn clear(&mut self) {
+        VecDeque::clear(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// The top of the stack is the back of the `LinkedList`
+impl<T> Lifo<T> for LinkedList<T> {
+    fn push(&mut self, data: T) {
+        self.push_back(data)
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.pop_back()
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.back()
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut T> {
+        self.back_mut()
+    }
+
+    fn len(&self) -> usize {
+        LinkedList::len(self)
+    }
+
+    fn clear(&mut self) {
+        LinkedList::clear(self)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::Lifo;
+    use crate::{ArrayStack, SliceStack, Stack, VecStack};
+    use core::mem::MaybeUninit;
+    use std::collections::{LinkedList, VecDeque};
+
+    fn exercise<S: Lifo<u8>>(mut stack: S) {
+        assert!(stack.is_empty());
+        stack.push(3);
+        stack.push(6);
+        stack.push(9);
+        assert_eq!(3, stack.len());
+        assert_eq!(Some(&9), stack.peek());
+        *stack.peek_mut().unwrap() = 10;
+        assert_eq!(Some(10), stack.pop());
+        assert_eq!(Some(6), stack.pop());
+        assert_eq!(1, stack.len());
+        stack.clear();
+        assert!(stack.is_empty());
+        assert_eq!(None, stack.pop());
+        assert_eq!(None, stack.peek_mut());
+    }
+
+    #[test]
+    fn implementations() {
+        exercise(Stack::new());
+        exercise(VecStack::new());
+        exercise(ArrayStack::<u8, 3>::new());
+        exercise(SliceStack::new(&mut [MaybeUninit::uninit(); 3]));
+        exercise(Vec::new());
+        exercise(VecDeque::new());
+        exercise(LinkedList::new());
+    }
+
+    #[test]
+    #[should_panic(expected = "full SliceStack")]
+    fn slice_overflow() {
+        let mut buffer = [MaybeUninit::uninit(); 1];
+        let mut stack = SliceStack::new(&mut buffer);
+        Lifo::push(&mut stack, 1);
+        Lifo::push(&mut stack, 2);
+    }
+}
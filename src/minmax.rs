@@ -0,0 +1,215 @@
+//! Min/Max Tracking Stack Data Structure
+//!
+//! `MinMaxStack` records, next to every element, where the minimum and maximum of the
+//! elements at or below it live, so `min` and `max` are O(1) after any push or pop
+//!
+//! Example
+//! ```rust
+//! use stack::MinMaxStack;
+//!
+//! let mut my_stack = MinMaxStack::<i32>::new();
+//! my_stack.push(4);
+//! my_stack.push(1);
+//! my_stack.push(7);
+//! assert_eq!(Some(&1), my_stack.min());
+//! assert_eq!(Some(&7), my_stack.max());
+//! my_stack.pop();
+//! assert_eq!(Some(&4), my_stack.max());
+//! ```
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+
+/// Stack Data Structure with O(1) minimum and maximum
+/// It has frames, holding each element with the positions of the extremes at or below it
+/// It has a comparator, used to order the elements
+pub struct MinMaxStack<T, F = fn(&T, &T) -> Ordering> {
+    frames: Vec<Frame<T>>,
+    compare: F,
+}
+
+struct Frame<T> {
+    value: T,
+    min: usize,
+    max: usize,
+}
+
+impl<T: Ord> MinMaxStack<T> {
+    /// Initialize a new empty stack ordering its elements by `Ord`
+    pub fn new() -> Self {
+        MinMaxStack::with_comparator(T::cmp)
+    }
+}
+
+impl<T> MinMaxStack<T> {
+    /// Initialize a new empty stack ordering its elements by the key `key` extracts
+    ///
+    /// Example
+    /// ```rust
+    /// # use stack::MinMaxStack;
+    /// let mut by_len = MinMaxStack::with_key(|s: &&str| s.len());
+    /// by_len.push("stack");
+    /// by_len.push("rs");
+    /// assert_eq!(Some(&"rs"), by_len.min());
+    /// ```
+    pub fn with_key<K, G>(key: G) -> MinMaxStack<T, impl Fn(&T, &T) -> Ordering>
+    where
+        K: Ord,
+        G: Fn(&T) -> K,
+    {
+        MinMaxStack::with_comparator(move |a: &T, b: &T| key(a).cmp(&key(b)))
+    }
+}
+
+impl<T, F: Fn(&T, &T) -> Ordering> MinMaxStack<T, F> {
+    /// Initialize a new empty stack ordering its elements by `compare`
+    ///
+    /// Example
+    /// ```rust
+    /// # use stack::MinMaxStack;
+    /// let mut reversed = MinMaxStack::with_comparator(|a: &i32, b: &i32| b.cmp(a));
+    /// reversed.push(1);
+    /// reversed.push(2);
+    /// assert_eq!(Some(&2), reversed.min());
+    /// ```
+    pub fn with_comparator(compare: F) -> Self {
+        MinMaxStack {
+            frames: Vec::new(),
+            compare,
+        }
+    }
+    /// Get a reference to data in the head of the stack
+    /// Returns None if the stack is empty
+    pub fn peek(&self) -> Option<&T> {
+        self.frames.last().map(|frame| &frame.value)
+    }
+    /// Pops the top off the stack and returns the data it contains
+    /// Returns None if the stack is empty
+    pub fn pop(&mut self) -> Option<T> {
+        self.frames.pop().map(|frame| frame.value)
+    }
+    /// Pushes the data onto the stack, updating the minimum and maximum [O(1) operation]
+    ///
+    /// On ties the older element stays the minimum or maximum
+    pub fn push(&mut self, data: T) {
+        let top = self.frames.len();
+        let (min, max) = match self.frames.last() {
+            Some(frame) => {
+                let min = match (self.compare)(&data, &self.frames[frame.min].value) {
+                    Ordering::Less => top,
+                    _ => frame.min,
+                };
+                let max = match (self.compare)(&data, &self.frames[frame.max].value) {
+                    Ordering::Greater => top,
+                    _ => frame.max,
+                };
+                (min, max)
+            }
+            None => (top, top),
+        };
+        self.frames.push(Frame {
+            value: data,
+            min,
+            max,
+        });
+    }
+    /// Get a reference to the smallest data in the stack [O(1) operation]
+    /// Returns None if the stack is empty
+    pub fn min(&self) -> Option<&T> {
+        let frame = self.frames.last()?;
+        Some(&self.frames[frame.min].value)
+    }
+    /// Get a reference to the largest data in the stack [O(1) operation]
+    /// Returns None if the stack is empty
+    pub fn max(&self) -> Option<&T> {
+        let frame = self.frames.last()?;
+        Some(&self.frames[frame.max].value)
+    }
+    /// Returns the number of elements in the stack
+    pub fn size(&self) -> usize {
+        self.frames.len()
+    }
+    /// Returns an iterator over references to the data, from the top of the stack to the bottom
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        self.frames.iter().rev().map(|frame| &frame.value)
+    }
+}
+
+impl<T: PartialEq, F: Fn(&T, &T) -> Ordering> MinMaxStack<T, F> {
+    /// Searches for data in the whole stack [O(n) operation]
+    /// Returns the position counted from the bottom of the stack, starting at 1
+    /// Returns None if the data is not found
+    pub fn search(&self, data: T) -> Option<usize> {
+        self.frames
+            .iter()
+            .rposition(|frame| frame.value == data)
+            .map(|i| i + 1)
+    }
+}
+
+impl<T: Ord> Default for MinMaxStack<T> {
+    fn default() -> Self {
+        MinMaxStack::new()
+    }
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for MinMaxStack<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.frames.iter().rev().map(|frame| &frame.value))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MinMaxStack;
+
+    #[test]
+    fn basics() {
+        let mut stack = MinMaxStack::<u8>::new();
+        assert_eq!(None, stack.min());
+        assert_eq!(None, stack.max());
+        for value in &[5, 3, 8, 3, 1, 9] {
+            stack.push(*value);
+        }
+        assert_eq!(6, stack.size());
+        assert_eq!(Some(&1), stack.min());
+        assert_eq!(Some(&9), stack.max());
+        assert_eq!(Some(9), stack.pop());
+        assert_eq!(Some(&8), stack.max());
+        assert_eq!(Some(1), stack.pop());
+        assert_eq!(Some(&3), stack.min());
+        assert_eq!(Some(4), stack.search(3));
+        stack.pop();
+        stack.pop();
+        assert_eq!(Some(&3), stack.min());
+        assert_eq!(Some(&5), stack.max());
+        assert_eq!(vec![&3, &5], stack.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn ties_keep_oldest() {
+        let mut stack = MinMaxStack::with_key(|pair: &(u8, char)| pair.0);
+        stack.push((1, 'a'));
+        stack.push((1, 'b'));
+        assert_eq!(Some(&(1, 'a')), stack.min());
+        assert_eq!(Some(&(1, 'a')), stack.max());
+        stack.push((0, 'c'));
+        assert_eq!(Some(&(0, 'c')), stack.min());
+        stack.pop();
+        assert_eq!(Some(&(1, 'a')), stack.min());
+    }
+
+    #[test]
+    fn comparator() {
+        let mut stack = MinMaxStack::with_comparator(|a: &f64, b: &f64| a.total_cmp(b));
+        stack.push(2.5);
+        stack.push(-1.0);
+        stack.push(7.25);
+        assert_eq!(Some(&-1.0), stack.min());
+        assert_eq!(Some(&7.25), stack.max());
+        assert_eq!(Some(&7.25), stack.peek());
+    }
+}
@@ -0,0 +1,258 @@
+//! Persistent Stack Data Structure
+//!
+//! `push` and `pop` never modify a stack, they return a new one that shares
+//! its tail with the original, so keeping many versions around costs O(1) per version
+//!
+//! `PersistentStack` shares its tiles through `Rc`, `ArcPersistentStack` through `Arc`
+//! and can be sent across threads
+//!
+//! Example
+//! ```rust
+//! use stack::PersistentStack;
+//!
+//! let base = PersistentStack::<i32>::new().push(1).push(2);
+//! let left = base.push(3);
+//! let right = base.pop().unwrap();
+//! assert_eq!(2, base.size);
+//! assert_eq!(Some(&3), left.peek());
+//! assert_eq!(Some(&1), right.peek());
+//! ```
+
+use alloc::rc::Rc;
+use alloc::sync::Arc;
+use core::iter::FusedIterator;
+
+macro_rules! persistent_stack {
+    ($(#[$attr:meta])* $name:ident, $tile:ident, $iter:ident, $ptr:ident) => {
+        $(#[$attr])*
+        #[derive(Debug)]
+        pub struct $name<T> {
+            head: Option<$ptr<$tile<T>>>,
+            pub size: usize,
+        }
+
+        #[derive(Debug)]
+        struct $tile<T> {
+            value: T,
+            next: Option<$ptr<$tile<T>>>,
+        }
+
+        impl<T> $name<T> {
+            /// Initialize a new empty stack with zero size
+            pub fn new() -> Self {
+                $name {
+                    head: None,
+                    size: 0,
+                }
+            }
+            /// Get a reference to data in the head of the stack
+            /// Returns None if the stack is empty
+            pub fn peek(&self) -> Option<&T> {
+                self.head.as_ref().map(|tile| &tile.value)
+            }
+            /// Returns a new stack with the data on top of this one [O(1) operation]
+            ///
+            /// The new stack shares all of its other tiles with this one
+            pub fn push(&self, data: T) -> Self {
+                $name {
+                    head: Some($ptr::new($tile {
+                        value: data,
+                        next: self.head.clone(),
+                    })),
+                    size: self.size + 1,
+                }
+            }
+            /// Returns a new stack without the top of this one [O(1) operation]
+            /// Returns None if the stack is empty
+            pub fn pop(&self) -> Option<Self> {
+                self.head.as_ref().map(|tile| $name {
+                    head: tile.next.clone(),
+                    size: self.size - 1,
+                })
+            }
+            /// Returns an iterator over references to the data, from the top of the stack to the bottom
+            pub fn iter(&self) -> $iter<'_, T> {
+                $iter {
+                    next: self.head.as_deref(),
+                    len: self.size,
+                }
+            }
+        }
+
+        impl<T: PartialEq> $name<T> {
+            /// Searches for data in the whole stack [O(n) operation]
+            /// Returns the position counted from the bottom of the stack, starting at 1
+            /// Returns None if the data is not found
+            pub fn search(&self, data: T) -> Option<usize> {
+                let mut pos = self.size;
+                for value in self.iter() {
+                    if *value == data {
+                        return Some(pos);
+                    }
+                    pos -= 1;
+                }
+                None
+            }
+        }
+
+        impl<T> Default for $name<T> {
+            fn default() -> Self {
+                $name::new()
+            }
+        }
+
+        /// Cloning only bumps the reference count of the head tile
+        impl<T> Clone for $name<T> {
+            fn clone(&self) -> Self {
+                $name {
+                    head: self.head.clone(),
+                    size: self.size,
+                }
+            }
+        }
+
+        /// Unlinks the tiles no other stack refers to one at a time,
+        /// stopping at the first tile that is still shared
+        ///
+        /// `into_inner` hands the tile to exactly one of the stacks dropping their last
+        /// references at once, so the rest of the chain is never left to a recursive drop
+        impl<T> Drop for $name<T> {
+            fn drop(&mut self) {
+                let mut ptr = self.head.take();
+                while let Some(tile) = ptr {
+                    ptr = $ptr::into_inner(tile).and_then(|mut tile| tile.next.take());
+                }
+            }
+        }
+
+        /// Borrowing iterator over a persistent stack
+        /// Yields the data from the top of the stack to the bottom
+        pub struct $iter<'a, T> {
+            next: Option<&'a $tile<T>>,
+            len: usize,
+        }
+
+        impl<'a, T> Iterator for $iter<'a, T> {
+            type Item = &'a T;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.next.map(|tile| {
+                    self.next = tile.next.as_deref();
+                    self.len -= 1;
+                    &tile.value
+                })
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.len, Some(self.len))
+            }
+        }
+
+        impl<T> ExactSizeIterator for $iter<'_, T> {}
+
+        impl<T> FusedIterator for $iter<'_, T> {}
+
+        impl<T> Clone for $iter<'_, T> {
+            fn clone(&self) -> Self {
+                $iter {
+                    next: self.next,
+                    len: self.len,
+                }
+            }
+        }
+
+        impl<'a, T> IntoIterator for &'a $name<T> {
+            type Item = &'a T;
+            type IntoIter = $iter<'a, T>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.iter()
+            }
+        }
+    };
+}
+
+persistent_stack!(
+    /// Persistent Stack Data Structure sharing its tiles through `Rc`
+    /// It has a head, that points to the top of the stack
+    /// It has a size, fixed for the lifetime of each version
+    PersistentStack,
+    RcTile,
+    Iter,
+    Rc
+);
+
+persistent_stack!(
+    /// Persistent Stack Data Structure sharing its tiles through `Arc`
+    /// It has a head, that points to the top of the stack
+    /// It has a size, fixed for the lifetime of each version
+    ArcPersistentStack,
+    ArcTile,
+    ArcIter,
+    Arc
+);
+
+#[cfg(test)]
+mod tests {
+    use super::{ArcPersistentStack, PersistentStack};
+
+    #[test]
+    fn basics() {
+        let empty = PersistentStack::<u8>::new();
+        let one = empty.push(3);
+        let two = one.push(6);
+        let three = two.push(9);
+        assert_eq!(0, empty.size);
+        assert_eq!(3, three.size);
+        assert_eq!(Some(&9), three.peek());
+        assert_eq!(Some(2), three.search(6));
+        assert_eq!(None, two.search(9));
+
+        let popped = three.pop().unwrap();
+        assert_eq!(2, popped.size);
+        assert_eq!(Some(&6), popped.peek());
+        assert_eq!(Some(&9), three.peek());
+        assert!(empty.pop().is_none());
+        assert_eq!(vec![&9, &6, &3], three.iter().collect::<Vec<_>>());
+        assert_eq!(vec![&6, &3], (&popped).into_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn shares_tail() {
+        let base = PersistentStack::<String>::new().push("tail".to_string());
+        let left = base.push("left".to_string());
+        let right = base.push("right".to_string());
+        let tail = |s: &PersistentStack<String>| s.iter().last().unwrap() as *const String;
+        assert_eq!(tail(&base), tail(&left));
+        assert_eq!(tail(&left), tail(&right));
+        drop(base);
+        drop(left);
+        assert_eq!(vec!["right", "tail"], right.iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn arc_across_threads() {
+        let base = ArcPersistentStack::<u32>::new().push(1).push(2);
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let base = base.clone();
+                std::thread::spawn(move || base.push(i).iter().sum::<u32>())
+            })
+            .collect();
+        for (i, handle) in handles.into_iter().enumerate() {
+            assert_eq!(3 + i as u32, handle.join().unwrap());
+        }
+        assert_eq!(2, base.size);
+    }
+
+    #[test]
+    fn drop_deep_stack() {
+        let mut stack = PersistentStack::<u32>::new();
+        for i in 0..1_000_000 {
+            stack = stack.push(i);
+        }
+        let shared = stack.pop().unwrap();
+        drop(stack);
+        assert_eq!(999_999, shared.size);
+    }
+}
@@ -0,0 +1,124 @@
+//! Free list of tile allocations, recycled by `Stack::pop` and reused by `Stack::push`
+
+use crate::Tile;
+use alloc::alloc::{dealloc, Layout};
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ptr::{self, NonNull};
+
+/// Holds the allocations of popped tiles, with their data already moved out
+/// It has a free list, boxed only while the limit is above 0 so a stack without a pool
+/// pays a single pointer
+pub(crate) struct Pool<T> {
+    free: Option<Box<FreeList<T>>>,
+}
+
+struct FreeList<T> {
+    tiles: Vec<NonNull<Tile<T>>>,
+    limit: usize,
+}
+
+impl<T> Pool<T> {
+    pub(crate) fn new(limit: usize) -> Self {
+        let mut pool = Pool { free: None };
+        pool.set_limit(limit);
+        pool
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.free.as_ref().map_or(0, |free| free.tiles.len())
+    }
+
+    pub(crate) fn limit(&self) -> usize {
+        self.free.as_ref().map_or(0, |free| free.limit)
+    }
+
+    pub(crate) fn set_limit(&mut self, limit: usize) {
+        if limit == 0 {
+            self.free = None;
+            return;
+        }
+        let free = self.free.get_or_insert_with(|| {
+            Box::new(FreeList {
+                tiles: Vec::new(),
+                limit,
+            })
+        });
+        free.limit = limit;
+        while free.tiles.len() > limit {
+            free.release_one();
+        }
+    }
+
+    /// Frees every retained allocation
+    pub(crate) fn shrink(&mut self) {
+        if let Some(free) = self.free.as_mut() {
+            free.release_all();
+            free.tiles.shrink_to_fit();
+        }
+    }
+
+    /// Allocates the tile, in a recycled allocation if one is available
+    pub(crate) fn alloc(&mut self, tile: Tile<T>) -> NonNull<Tile<T>> {
+        match self.free.as_mut().and_then(|free| free.tiles.pop()) {
+            // Every pointer in `tiles` came from `Box::leak` and holds no live tile
+            Some(raw) => unsafe {
+                ptr::write(raw.as_ptr(), tile);
+                raw
+            },
+            None => NonNull::from(Box::leak(Box::new(tile))),
+        }
+    }
+
+    /// Moves the data out of a tile with no `next`, keeping its allocation if under the limit
+    ///
+    /// # Safety
+    ///
+    /// The tile must come from `alloc`, and be linked from nowhere so nothing uses it afterwards
+    pub(crate) unsafe fn recycle(&mut self, tile: NonNull<Tile<T>>) -> T {
+        debug_assert!((*tile.as_ptr()).next.is_none());
+        match self.free.as_mut() {
+            Some(free) if free.tiles.len() < free.limit => {
+                // `next` is None so nothing else in the tile needs dropping
+                let value = ptr::read(&(*tile.as_ptr()).value);
+                free.tiles.push(tile);
+                value
+            }
+            _ => Box::from_raw(tile.as_ptr()).value,
+        }
+    }
+}
+
+impl<T> FreeList<T> {
+    fn release_one(&mut self) {
+        if let Some(raw) = self.tiles.pop() {
+            unsafe { dealloc(raw.as_ptr().cast(), Layout::new::<Tile<T>>()) };
+        }
+    }
+
+    fn release_all(&mut self) {
+        while !self.tiles.is_empty() {
+            self.release_one();
+        }
+    }
+}
+
+impl<T> Drop for FreeList<T> {
+    fn drop(&mut self) {
+        self.release_all();
+    }
+}
+
+impl<T> fmt::Debug for Pool<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pool")
+            .field("len", &self.len())
+            .field("limit", &self.limit())
+            .finish()
+    }
+}
+
+// The pool only owns uninitialized allocations, never a value of T
+unsafe impl<T> Send for Pool<T> {}
+unsafe impl<T> Sync for Pool<T> {}
@@ -0,0 +1,314 @@
+//! Reverse Polish Notation Evaluator
+//!
+//! An `Evaluator` reads whitespace separated tokens from left to right: operands are
+//! pushed onto a `Stack`, and operators pop their arguments and push their result
+//!
+//! `Evaluator::integers` and `Evaluator::floats` come with the arithmetic operators,
+//! and `operator` adds entries to the table of any evaluator
+//!
+//! Example
+//! ```rust
+//! use stack::rpn::{Evaluator, RpnError};
+//!
+//! let integers = Evaluator::integers();
+//! assert_eq!(Ok(14), integers.evaluate("5 1 2 + 4 * + 3 -"));
+//! assert_eq!(
+//!     Err(RpnError::Underflow { token: 2, needed: 2, available: 1 }),
+//!     integers.evaluate("1 +")
+//! );
+//!
+//! let floats = Evaluator::floats().operator("hypot", 2, |args: &[f64]| Ok(args[0].hypot(args[1])));
+//! assert_eq!(Ok(5.0), floats.evaluate("3 4 hypot"));
+//! ```
+
+use crate::{Lifo, Stack};
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+/// Error produced while evaluating an expression
+/// Token positions count the whitespace separated tokens from 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RpnError {
+    /// The expression has no tokens
+    Empty,
+    /// An operator needed more operands than the stack held
+    Underflow {
+        token: usize,
+        needed: usize,
+        available: usize,
+    },
+    /// The expression left more than one operand on the stack
+    Leftover { count: usize },
+    /// A token is neither a known operator nor a valid operand
+    UnknownOperator { token: usize, text: String },
+    /// An operator rejected its operands, e.g. on division by zero
+    Operator { token: usize, message: String },
+}
+
+impl fmt::Display for RpnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RpnError::Empty => write!(f, "empty expression"),
+            RpnError::Underflow {
+                token,
+                needed,
+                available,
+            } => write!(
+                f,
+                "stack underflow at token {}: needed {} operands, found {}",
+                token, needed, available
+            ),
+            RpnError::Leftover { count } => {
+                write!(f, "{} operands left on the stack, expected 1", count)
+            }
+            RpnError::UnknownOperator { token, text } => {
+                write!(f, "unknown operator `{}` at token {}", text, token)
+            }
+            RpnError::Operator { token, message } => write!(f, "{} at token {}", message, token),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RpnError {}
+
+/// Function applied by an operator to its operands, in the order they were pushed
+pub type OperatorFn<T> = Box<dyn Fn(&[T]) -> Result<T, String>>;
+
+struct Operator<T> {
+    arity: usize,
+    apply: OperatorFn<T>,
+}
+
+/// Evaluates Reverse Polish Notation expressions over operands of type T
+/// It has a table of operators, each with its arity
+pub struct Evaluator<T> {
+    operators: BTreeMap<String, Operator<T>>,
+}
+
+impl<T> Evaluator<T> {
+    /// Initialize a new evaluator with no operators
+    pub fn new() -> Self {
+        Evaluator {
+            operators: BTreeMap::new(),
+        }
+    }
+    /// Adds an operator taking `arity` operands, replacing any operator with the same symbol
+    ///
+    /// Tokens are looked up as operators before being parsed as operands,
+    /// so `-` is subtraction while `-3` is still a negative number
+    pub fn operator<F>(mut self, symbol: &str, arity: usize, apply: F) -> Self
+    where
+        F: Fn(&[T]) -> Result<T, String> + 'static,
+    {
+        self.operators.insert(
+            symbol.to_string(),
+            Operator {
+                arity,
+                apply: Box::new(apply),
+            },
+        );
+        self
+    }
+}
+
+impl<T: FromStr> Evaluator<T> {
+    /// Evaluates the expression on a fresh `Stack`, returning the single operand left
+    pub fn evaluate(&self, expr: &str) -> Result<T, RpnError> {
+        let mut operands = Stack::new();
+        self.evaluate_on(expr, &mut operands)?;
+        match operands.size {
+            0 => Err(RpnError::Empty),
+            1 => Ok(operands.pop().unwrap()),
+            count => Err(RpnError::Leftover { count }),
+        }
+    }
+    /// Evaluates the expression on top of the operands already in `operands`,
+    /// leaving every result on it
+    ///
+    /// Example
+    /// ```rust
+    /// # use stack::rpn::Evaluator;
+    /// let mut operands = vec![10];
+    /// Evaluator::<i64>::integers().evaluate_on("2 3 *", &mut operands).unwrap();
+    /// assert_eq!(vec![10, 6], operands);
+    /// ```
+    pub fn evaluate_on<S: Lifo<T>>(&self, expr: &str, operands: &mut S) -> Result<(), RpnError> {
+        for (i, text) in expr.split_whitespace().enumerate() {
+            let token = i + 1;
+            if let Some(operator) = self.operators.get(text) {
+                let available = operands.len();
+                if available < operator.arity {
+                    return Err(RpnError::Underflow {
+                        token,
+                        needed: operator.arity,
+                        available,
+                    });
+                }
+                let mut args: Vec<T> = (0..operator.arity)
+                    .map(|_| operands.pop().unwrap())
+                    .collect();
+                args.reverse();
+                let result = (operator.apply)(&args)
+                    .map_err(|message| RpnError::Operator { token, message })?;
+                operands.push(result);
+            } else {
+                let operand = text.parse().map_err(|_| RpnError::UnknownOperator {
+                    token,
+                    text: text.to_string(),
+                })?;
+                operands.push(operand);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Evaluator<i64> {
+    /// Initialize a new evaluator over integers with `+`, `-`, `*`, `/` and `%`,
+    /// reporting overflow and division by zero as operator errors
+    pub fn integers() -> Self {
+        fn checked(
+            op: fn(i64, i64) -> Option<i64>,
+            message: &'static str,
+        ) -> impl Fn(&[i64]) -> Result<i64, String> {
+            move |args| op(args[0], args[1]).ok_or_else(|| message.to_string())
+        }
+        Evaluator::new()
+            .operator("+", 2, checked(i64::checked_add, "overflow"))
+            .operator("-", 2, checked(i64::checked_sub, "overflow"))
+            .operator("*", 2, checked(i64::checked_mul, "overflow"))
+            .operator(
+                "/",
+                2,
+                checked(i64::checked_div, "division by zero or overflow"),
+            )
+            .operator(
+                "%",
+                2,
+                checked(i64::checked_rem, "division by zero or overflow"),
+            )
+    }
+}
+
+impl Evaluator<f64> {
+    /// Initialize a new evaluator over floats with `+`, `-`, `*`, `/`, and `^` with `std`
+    pub fn floats() -> Self {
+        let floats = Evaluator::new()
+            .operator("+", 2, |args: &[f64]| Ok(args[0] + args[1]))
+            .operator("-", 2, |args: &[f64]| Ok(args[0] - args[1]))
+            .operator("*", 2, |args: &[f64]| Ok(args[0] * args[1]))
+            .operator("/", 2, |args: &[f64]| Ok(args[0] / args[1]));
+        // `^` needs the float math of `std`
+        #[cfg(feature = "std")]
+        let floats = floats.operator("^", 2, |args: &[f64]| Ok(args[0].powf(args[1])));
+        floats
+    }
+}
+
+impl<T> Default for Evaluator<T> {
+    fn default() -> Self {
+        Evaluator::new()
+    }
+}
+
+impl<T> fmt::Debug for Evaluator<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut symbols: Vec<&String> = self.operators.keys().collect();
+        symbols.sort();
+        f.debug_struct("Evaluator")
+            .field("operators", &symbols)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Evaluator, RpnError};
+
+    #[test]
+    fn integers() {
+        let rpn = Evaluator::integers();
+        assert_eq!(Ok(7), rpn.evaluate("7"));
+        assert_eq!(Ok(-3), rpn.evaluate("2 5 -"));
+        assert_eq!(Ok(-4), rpn.evaluate("-3 1 - "));
+        assert_eq!(Ok(1), rpn.evaluate("17 5 % 1 -"));
+        assert_eq!(Ok(14), rpn.evaluate("5 1 2 + 4 * + 3 -"));
+    }
+
+    #[test]
+    fn errors() {
+        let rpn = Evaluator::integers();
+        assert_eq!(Err(RpnError::Empty), rpn.evaluate("  "));
+        assert_eq!(
+            Err(RpnError::Underflow {
+                token: 4,
+                needed: 2,
+                available: 1
+            }),
+            rpn.evaluate("1 2 + *")
+        );
+        assert_eq!(Err(RpnError::Leftover { count: 3 }), rpn.evaluate("1 2 3"));
+        let unknown = rpn.evaluate("1 2 ^");
+        assert_eq!(
+            Err(RpnError::UnknownOperator {
+                token: 3,
+                text: "^".to_string()
+            }),
+            unknown
+        );
+        assert_eq!(
+            "unknown operator `^` at token 3",
+            unknown.unwrap_err().to_string()
+        );
+        assert_eq!(
+            Err(RpnError::Operator {
+                token: 3,
+                message: "division by zero or overflow".to_string()
+            }),
+            rpn.evaluate("1 0 /")
+        );
+    }
+
+    #[test]
+    fn floats() {
+        let rpn = Evaluator::floats();
+        assert_eq!(Ok(2.5), rpn.evaluate("10 4 /"));
+        assert_eq!(Ok(-1.5), rpn.evaluate("1 2.5 -"));
+        #[cfg(feature = "std")]
+        assert!((rpn.evaluate("2 3 ^").unwrap() - 8.0).abs() < 1e-9);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn floats_and_custom_operators() {
+        let rpn = Evaluator::floats()
+            .operator("neg", 1, |args: &[f64]| Ok(-args[0]))
+            .operator("sqrt", 1, |args: &[f64]| {
+                if args[0] < 0.0 {
+                    Err("square root of a negative number".to_string())
+                } else {
+                    Ok(args[0].sqrt())
+                }
+            });
+        assert_eq!(Ok(2.5), rpn.evaluate("10 4 /"));
+        // powf and sqrt are not exact, so compare within a tolerance
+        assert!((rpn.evaluate("2 3 ^ neg").unwrap() + 8.0).abs() < 1e-9);
+        assert!((rpn.evaluate("3 2 ^ sqrt").unwrap() - 3.0).abs() < 1e-9);
+        assert_eq!(
+            Err(RpnError::Operator {
+                token: 2,
+                message: "square root of a negative number".to_string()
+            }),
+            rpn.evaluate("-1 sqrt")
+        );
+
+        let min =
+            Evaluator::new().operator("min3", 3, |args: &[u8]| Ok(*args.iter().min().unwrap()));
+        assert_eq!(Ok(1), min.evaluate("4 1 9 min3"));
+    }
+}
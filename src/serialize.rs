@@ -0,0 +1,243 @@
+//! Serde support, enabled by the `serde` feature
+//!
+//! Stacks serialize as a sequence from the top of the stack to the bottom, the same
+//! order their iterators yield, and deserializing rebuilds the same stack with its size
+//!
+//! Use `bottom_to_top` with `#[serde(with = "...")]` on a `Stack` field to store the
+//! sequence in push order instead
+//!
+//! Example
+//! ```rust
+//! use stack::Stack;
+//!
+//! let mut my_stack = Stack::<i32>::new();
+//! my_stack.push(1);
+//! my_stack.push(2);
+//! let json = serde_json::to_string(&my_stack).unwrap();
+//! assert_eq!("[2,1]", json);
+//! let restored: Stack<i32> = serde_json::from_str(&json).unwrap();
+//! assert_eq!(2, restored.size);
+//! ```
+
+use crate::{ArcPersistentStack, PersistentStack, Stack, VecStack};
+use ::serde::de::{Deserialize, Deserializer, SeqAccess, Visitor};
+use ::serde::ser::{Serialize, SerializeSeq, Serializer};
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+
+impl<T: Serialize> Serialize for Stack<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_seq(serializer, self.size, self.iter())
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Stack<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(TopToBottom(PhantomData))
+    }
+}
+
+impl<T: Serialize> Serialize for VecStack<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_seq(serializer, self.size, self.iter())
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for VecStack<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = Vec::<T>::deserialize(deserializer)?;
+        let mut stack = VecStack::with_capacity(data.len());
+        for value in data.into_iter().rev() {
+            stack.push(value);
+        }
+        Ok(stack)
+    }
+}
+
+impl<T: Serialize> Serialize for PersistentStack<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_seq(serializer, self.size, self.iter())
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for PersistentStack<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = Vec::<T>::deserialize(deserializer)?;
+        Ok(data
+            .into_iter()
+            .rev()
+            .fold(PersistentStack::new(), |stack, value| stack.push(value)))
+    }
+}
+
+impl<T: Serialize> Serialize for ArcPersistentStack<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_seq(serializer, self.size, self.iter())
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for ArcPersistentStack<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = Vec::<T>::deserialize(deserializer)?;
+        Ok(data
+            .into_iter()
+            .rev()
+            .fold(ArcPersistentStack::new(), |stack, value| stack.push(value)))
+    }
+}
+
+/// Serializes and deserializes a `Stack` as a sequence from the bottom of the stack to the top
+///
+/// Example
+/// ```rust
+/// use serde::{Deserialize, Serialize};
+/// use stack::Stack;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct History {
+///     #[serde(with = "stack::serialize::bottom_to_top")]
+///     edits: Stack<String>,
+/// }
+/// ```
+pub mod bottom_to_top {
+    use super::serialize_seq;
+    use crate::Stack;
+    use ::serde::de::{Deserialize, Deserializer};
+    use ::serde::ser::Serializer;
+    use alloc::vec::Vec;
+
+    /// Serializes the stack in the order its data was pushed
+    pub fn serialize<T, S>(stack: &Stack<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: ::serde::Serialize,
+        S: Serializer,
+    {
+        let values: Vec<&T> = stack.iter().collect();
+        serialize_seq(serializer, stack.size, values.into_iter().rev())
+    }
+
+    /// Deserializes the stack by pushing the data in sequence order
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Stack<T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        let mut stack = Stack::new();
+        for value in Vec::<T>::deserialize(deserializer)? {
+            stack.push(value);
+        }
+        Ok(stack)
+    }
+}
+
+fn serialize_seq<'a, T, S, I>(serializer: S, len: usize, values: I) -> Result<S::Ok, S::Error>
+where
+    T: Serialize + 'a,
+    S: Serializer,
+    I: Iterator<Item = &'a T>,
+{
+    let mut seq = serializer.serialize_seq(Some(len))?;
+    for value in values {
+        seq.serialize_element(value)?;
+    }
+    seq.end()
+}
+
+/// Builds a `Stack` from a sequence listed from the top of the stack to the bottom,
+/// appending each tile below the previous one
+struct TopToBottom<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for TopToBottom<T> {
+    type Value = Stack<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a sequence")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut stack = Stack::new();
+        while let Some(value) = seq.next_element()? {
+            stack.push_bottom(value);
+        }
+        Ok(stack)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{PersistentStack, Stack, VecStack};
+    use serde::{Deserialize, Serialize};
+
+    fn sample() -> Stack<String> {
+        let mut stack = Stack::new();
+        for value in &["bottom", "middle", "top"] {
+            stack.push(value.to_string());
+        }
+        stack
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let json = serde_json::to_string(&sample()).unwrap();
+        assert_eq!(r#"["top","middle","bottom"]"#, json);
+        let mut stack: Stack<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(3, stack.size);
+        assert_eq!(Some("top".to_string()), stack.pop());
+        assert_eq!(Some(2), stack.search("middle".to_string()));
+        let mut base = Stack::new();
+        base.push("base".to_string());
+        base.append(&mut stack);
+        assert_eq!(
+            vec!["middle", "bottom", "base"],
+            base.iter().collect::<Vec<_>>()
+        );
+        let empty: Stack<u8> = serde_json::from_str("[]").unwrap();
+        assert_eq!(0, empty.size);
+    }
+
+    #[test]
+    fn bincode_round_trip() {
+        let bytes = bincode::serialize(&sample()).unwrap();
+        let stack: Stack<String> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(3, stack.size);
+        assert_eq!(
+            vec!["top", "middle", "bottom"],
+            stack.iter().collect::<Vec<_>>()
+        );
+
+        let mut vec_stack = VecStack::new();
+        vec_stack.push(1u32);
+        vec_stack.push(2);
+        let bytes = bincode::serialize(&vec_stack).unwrap();
+        let vec_stack: VecStack<u32> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(2, vec_stack.size);
+        assert_eq!(Some(&2), vec_stack.peek());
+    }
+
+    #[test]
+    fn persistent_round_trip() {
+        let stack = PersistentStack::new().push(1u8).push(2);
+        let json = serde_json::to_string(&stack).unwrap();
+        assert_eq!("[2,1]", json);
+        let stack: PersistentStack<u8> = serde_json::from_str(&json).unwrap();
+        assert_eq!(2, stack.size);
+        assert_eq!(Some(&2), stack.peek());
+    }
+
+    #[test]
+    fn bottom_to_top() {
+        #[derive(Serialize, Deserialize)]
+        struct History {
+            #[serde(with = "crate::serialize::bottom_to_top")]
+            edits: Stack<String>,
+        }
+
+        let history = History { edits: sample() };
+        let json = serde_json::to_string(&history).unwrap();
+        assert_eq!(r#"{"edits":["bottom","middle","top"]}"#, json);
+        let history: History = serde_json::from_str(&json).unwrap();
+        assert_eq!(3, history.edits.size);
+        assert_eq!(Some(&"top".to_string()), history.edits.peek());
+    }
+}
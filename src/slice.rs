@@ -0,0 +1,287 @@
+//! Allocation-free Stack Data Structure
+//!
+//! `SliceStack` keeps its elements in a buffer borrowed from the caller, so it works
+//! without an allocator: the buffer can live on the call stack or in a `static`
+//!
+//! Example
+//! ```rust
+//! use core::mem::MaybeUninit;
+//! use stack::{Full, SliceStack};
+//!
+//! let mut buffer = [MaybeUninit::<u32>::uninit(); 2];
+//! let mut stack = SliceStack::new(&mut buffer);
+//! assert_eq!(Ok(()), stack.push(1));
+//! assert_eq!(Ok(()), stack.push(2));
+//! assert_eq!(Err(Full(3)), stack.push(3));
+//! assert_eq!(Some(2), stack.pop());
+//! ```
+
+use crate::Full;
+use core::fmt;
+use core::iter::Rev;
+use core::mem::MaybeUninit;
+use core::{ptr, slice};
+
+/// Stack Data Structure over a caller-provided buffer
+/// It has slots, borrowed from the caller and holding the data with the top of the stack last
+pub struct SliceStack<'a, T> {
+    slots: Slots<&'a mut [MaybeUninit<T>]>,
+}
+
+/// Storage of possibly uninitialized slots, borrowed as by `SliceStack` or inline as by `ArrayStack`
+pub(crate) trait Buffer {
+    type Item;
+    fn slots(&self) -> &[MaybeUninit<Self::Item>];
+    fn slots_mut(&mut self) -> &mut [MaybeUninit<Self::Item>];
+}
+
+impl<T> Buffer for &mut [MaybeUninit<T>] {
+    type Item = T;
+
+    fn slots(&self) -> &[MaybeUninit<T>] {
+        self
+    }
+
+    fn slots_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        self
+    }
+}
+
+impl<T, const N: usize> Buffer for [MaybeUninit<T>; N] {
+    type Item = T;
+
+    fn slots(&self) -> &[MaybeUninit<T>] {
+        self
+    }
+
+    fn slots_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        self
+    }
+}
+
+/// Stack over the slots of a buffer, shared by `SliceStack` and `ArrayStack`
+/// It has a buffer, whose first size slots hold the data with the top of the stack last
+/// It has a size, kept private since the slots above it are uninitialized
+pub(crate) struct Slots<B: Buffer> {
+    buffer: B,
+    size: usize,
+}
+
+impl<B: Buffer> Slots<B> {
+    pub(crate) const fn new(buffer: B) -> Self {
+        Slots { buffer, size: 0 }
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<B::Item> {
+        if self.size == 0 {
+            return None;
+        }
+        self.size -= 1;
+        // The slot was initialized and is now outside the stack, so it is read only once
+        Some(unsafe { self.buffer.slots()[self.size].as_ptr().read() })
+    }
+
+    pub(crate) fn push(&mut self, data: B::Item) -> Result<(), Full<B::Item>> {
+        match self.buffer.slots_mut().get_mut(self.size) {
+            Some(slot) => {
+                *slot = MaybeUninit::new(data);
+                self.size += 1;
+                Ok(())
+            }
+            None => Err(Full(data)),
+        }
+    }
+
+    pub(crate) fn size(&self) -> usize {
+        self.size
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.buffer.slots().len()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        let live: *mut [B::Item] = self.as_mut_slice();
+        // Reset the size first, so a panicking destructor cannot cause a double drop
+        self.size = 0;
+        unsafe { ptr::drop_in_place(live) };
+    }
+
+    pub(crate) fn as_slice(&self) -> &[B::Item] {
+        // The first `size` slots are initialized
+        unsafe { slice::from_raw_parts(self.buffer.slots().as_ptr().cast(), self.size) }
+    }
+
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [B::Item] {
+        unsafe { slice::from_raw_parts_mut(self.buffer.slots_mut().as_mut_ptr().cast(), self.size) }
+    }
+}
+
+/// Drops only the initialized elements, the slots themselves belong to the buffer
+impl<B: Buffer> Drop for Slots<B> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<'a, T> SliceStack<'a, T> {
+    /// Initialize a new empty stack holding at most `buffer.len()` elements
+    ///
+    /// Example
+    /// ```rust
+    /// # use core::mem::MaybeUninit;
+    /// # use stack::SliceStack;
+    /// let mut buffer = [MaybeUninit::<i32>::uninit(); 16];
+    /// let new_stack = SliceStack::new(&mut buffer);
+    /// ```
+    pub fn new(buffer: &'a mut [MaybeUninit<T>]) -> Self {
+        SliceStack {
+            slots: Slots::new(buffer),
+        }
+    }
+    /// Get a reference to data in the head of the stack
+    /// Returns None if the stack is empty
+    pub fn peek(&self) -> Option<&T> {
+        self.as_slice().last()
+    }
+    /// Get a mutable reference to data in the head of the stack
+    /// Returns None if the stack is empty
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        self.as_mut_slice().last_mut()
+    }
+    /// Pops the top off the stack and returns the data it contains
+    /// Returns None if the stack is empty
+    pub fn pop(&mut self) -> Option<T> {
+        self.slots.pop()
+    }
+    /// Pushes the data onto the stack
+    /// Hands the data back in `Full` if the buffer has no slot left
+    pub fn push(&mut self, data: T) -> Result<(), Full<T>> {
+        self.slots.push(data)
+    }
+    /// Returns the number of elements in the stack
+    pub fn size(&self) -> usize {
+        self.slots.size()
+    }
+    /// Returns the maximum number of elements the stack can hold
+    pub fn capacity(&self) -> usize {
+        self.slots.capacity()
+    }
+    /// Returns true if a push would be rejected
+    pub fn is_full(&self) -> bool {
+        self.size() == self.capacity()
+    }
+    /// Returns the number of elements that can be pushed before the stack is full
+    pub fn remaining(&self) -> usize {
+        self.capacity() - self.size()
+    }
+    /// Removes all the elements from the stack
+    pub fn clear(&mut self) {
+        self.slots.clear();
+    }
+    /// Returns the data as a slice, from the bottom of the stack to the top
+    pub fn as_slice(&self) -> &[T] {
+        self.slots.as_slice()
+    }
+    /// Returns the data as a mutable slice, from the bottom of the stack to the top
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self.slots.as_mut_slice()
+    }
+    /// Returns an iterator over references to the data, from the top of the stack to the bottom
+    pub fn iter(&self) -> Rev<slice::Iter<'_, T>> {
+        self.as_slice().iter().rev()
+    }
+    /// Returns an iterator over mutable references to the data, from the top of the stack to the bottom
+    pub fn iter_mut(&mut self) -> Rev<slice::IterMut<'_, T>> {
+        self.as_mut_slice().iter_mut().rev()
+    }
+}
+
+impl<T: PartialEq> SliceStack<'_, T> {
+    /// Searches for data in the whole stack [O(n) operation]
+    /// Returns the position counted from the bottom of the stack, starting at 1
+    /// Returns None if the data is not found
+    pub fn search(&self, data: T) -> Option<usize> {
+        self.as_slice()
+            .iter()
+            .rposition(|value| *value == data)
+            .map(|index| index + 1)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SliceStack<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SliceStack")
+            .field("data", &self.as_slice())
+            .field("size", &self.size())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SliceStack<'_, T> {
+    type Item = &'a T;
+    type IntoIter = Rev<slice::Iter<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut SliceStack<'_, T> {
+    type Item = &'a mut T;
+    type IntoIter = Rev<slice::IterMut<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SliceStack;
+    use crate::Full;
+    use core::mem::MaybeUninit;
+    use std::rc::Rc;
+
+    #[test]
+    fn basics() {
+        let mut buffer = [MaybeUninit::<u8>::uninit(); 3];
+        let mut stack = SliceStack::new(&mut buffer);
+        assert_eq!(3, stack.capacity());
+        stack.push(3).unwrap();
+        stack.push(6).unwrap();
+        stack.push(9).unwrap();
+        assert!(stack.is_full());
+        assert_eq!(Err(Full(12)), stack.push(12));
+        assert_eq!(Some(&9), stack.peek());
+        assert_eq!(Some(2), stack.search(6));
+        assert_eq!(vec![&9, &6, &3], stack.iter().collect::<Vec<_>>());
+        assert_eq!(Some(9), stack.pop());
+        *stack.peek_mut().unwrap() += 1;
+        assert_eq!(&[3, 7], stack.as_slice());
+        assert_eq!(1, stack.remaining());
+        stack.clear();
+        assert_eq!(0, stack.size());
+        assert_eq!(None, stack.pop());
+    }
+
+    #[test]
+    fn drops_initialized_only() {
+        let counter = Rc::new(());
+        let mut buffer: [MaybeUninit<Rc<()>>; 4] = [
+            MaybeUninit::uninit(),
+            MaybeUninit::uninit(),
+            MaybeUninit::uninit(),
+            MaybeUninit::uninit(),
+        ];
+        let mut stack = SliceStack::new(&mut buffer);
+        for _ in 0..3 {
+            stack.push(Rc::clone(&counter)).unwrap();
+        }
+        drop(stack.pop());
+        assert_eq!(3, Rc::strong_count(&counter));
+        drop(stack);
+        assert_eq!(1, Rc::strong_count(&counter));
+    }
+}
@@ -0,0 +1,344 @@
+//! Undo/Redo History
+//!
+//! `UndoHistory` keeps the undo and redo stacks of an editor-like application: executing
+//! a `Command` applies it and clears the redo stack, undoing reverts the most recent entry
+//! and moves it onto the redo stack, and redoing applies it again
+//!
+//! Consecutive commands can coalesce into a single entry through `Command::merge`,
+//! commands executed between `begin` and `commit` form one transaction that is undone
+//! and redone as a whole, and `with_limit` drops the oldest entries past a depth
+//!
+//! Example
+//! ```rust
+//! use stack::undo::{Command, UndoHistory};
+//!
+//! struct Append(String);
+//!
+//! impl Command for Append {
+//!     type Target = String;
+//!
+//!     fn apply(&mut self, target: &mut String) {
+//!         target.push_str(&self.0);
+//!     }
+//!
+//!     fn revert(&mut self, target: &mut String) {
+//!         target.truncate(target.len() - self.0.len());
+//!     }
+//! }
+//!
+//! let mut text = String::new();
+//! let mut history = UndoHistory::new();
+//! history.execute(Append("hello".to_string()), &mut text);
+//! history.execute(Append(" world".to_string()), &mut text);
+//! history.undo(&mut text);
+//! assert_eq!("hello", text);
+//! history.redo(&mut text);
+//! assert_eq!("hello world", text);
+//! ```
+
+use crate::{BoundedStack, Overflow, Stack};
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// An action that can be applied to a target and reverted afterwards
+pub trait Command: Sized {
+    /// The type of the state the command edits
+    type Target;
+    /// Performs the action on the target
+    fn apply(&mut self, target: &mut Self::Target);
+    /// Undoes the action, restoring the target to its state before `apply`
+    fn revert(&mut self, target: &mut Self::Target);
+    /// Tries to absorb `next`, which has already been applied, into this command
+    ///
+    /// Returns `next` back if the two cannot be merged, which is the default
+    fn merge(&mut self, next: Self) -> Result<(), Self> {
+        Err(next)
+    }
+}
+
+/// Undo/Redo History Data Structure
+/// It has an undo stack, holding the applied entries with the most recent on top
+/// in a ring buffer, so the oldest entry past the limit is dropped in O(1)
+/// It has a redo stack, holding the undone entries with the most recently undone on top
+/// Each entry is a group of commands, undone and redone together
+#[derive(Debug)]
+pub struct UndoHistory<C> {
+    undo: BoundedStack<Vec<C>>,
+    redo: Stack<Vec<C>>,
+    transaction: Option<Transaction<C>>,
+    mergeable: bool,
+}
+
+#[derive(Debug)]
+struct Transaction<C> {
+    depth: usize,
+    commands: Vec<C>,
+}
+
+impl<C: Command> UndoHistory<C> {
+    /// Initialize a new history keeping every entry
+    pub fn new() -> Self {
+        UndoHistory::with_limit(usize::MAX)
+    }
+    /// Initialize a new history keeping at most `limit` undo entries,
+    /// dropping the oldest one when a new entry would exceed it [O(1) operation]
+    pub fn with_limit(limit: usize) -> Self {
+        UndoHistory {
+            undo: BoundedStack::new(limit, Overflow::EvictBottom),
+            redo: Stack::new(),
+            transaction: None,
+            mergeable: false,
+        }
+    }
+    /// Applies the command to the target and records it, clearing the redo stack
+    ///
+    /// Outside a transaction the command is first offered to the most recent entry
+    /// through `Command::merge`, unless `seal` was called since that entry was recorded
+    pub fn execute(&mut self, mut command: C, target: &mut C::Target) {
+        command.apply(target);
+        self.redo.clear();
+        if let Some(transaction) = self.transaction.as_mut() {
+            transaction.commands.push(command);
+            return;
+        }
+        if self.mergeable {
+            if let Some(last) = self.undo.peek_mut().and_then(|group| group.last_mut()) {
+                match last.merge(command) {
+                    Ok(()) => return,
+                    Err(next) => command = next,
+                }
+            }
+        }
+        self.record(vec![command]);
+        self.mergeable = true;
+    }
+    /// Reverts the most recent entry and moves it onto the redo stack
+    /// Returns false if there is nothing to undo, or a transaction is open
+    pub fn undo(&mut self, target: &mut C::Target) -> bool {
+        if self.transaction.is_some() {
+            return false;
+        }
+        match self.undo.pop() {
+            Some(mut group) => {
+                for command in group.iter_mut().rev() {
+                    command.revert(target);
+                }
+                self.redo.push(group);
+                self.mergeable = false;
+                true
+            }
+            None => false,
+        }
+    }
+    /// Applies the most recently undone entry again and moves it back onto the undo stack
+    /// Returns false if there is nothing to redo, or a transaction is open
+    pub fn redo(&mut self, target: &mut C::Target) -> bool {
+        if self.transaction.is_some() {
+            return false;
+        }
+        match self.redo.pop() {
+            Some(mut group) => {
+                for command in group.iter_mut() {
+                    command.apply(target);
+                }
+                self.record(group);
+                self.mergeable = false;
+                true
+            }
+            None => false,
+        }
+    }
+    /// Opens a transaction: every command executed until the matching `commit`
+    /// is recorded as a single entry
+    ///
+    /// Transactions nest, only the outermost `commit` records the entry
+    pub fn begin(&mut self) {
+        match self.transaction.as_mut() {
+            Some(transaction) => transaction.depth += 1,
+            None => {
+                self.transaction = Some(Transaction {
+                    depth: 1,
+                    commands: Vec::new(),
+                })
+            }
+        }
+    }
+    /// Closes the innermost open transaction
+    /// Returns false if no transaction is open
+    pub fn commit(&mut self) -> bool {
+        let transaction = match self.transaction.as_mut() {
+            Some(transaction) => transaction,
+            None => return false,
+        };
+        transaction.depth -= 1;
+        if transaction.depth == 0 {
+            let commands = self.transaction.take().unwrap().commands;
+            if !commands.is_empty() {
+                self.record(commands);
+            }
+            self.mergeable = false;
+        }
+        true
+    }
+    /// Stops the next command from merging into the most recent entry
+    pub fn seal(&mut self) {
+        self.mergeable = false;
+    }
+    /// Returns true if there is an entry to undo
+    pub fn can_undo(&self) -> bool {
+        self.undo.size() > 0
+    }
+    /// Returns true if there is an entry to redo
+    pub fn can_redo(&self) -> bool {
+        self.redo.size > 0
+    }
+    /// Returns the number of entries that can be undone
+    pub fn undo_len(&self) -> usize {
+        self.undo.size()
+    }
+    /// Returns the number of entries that can be redone
+    pub fn redo_len(&self) -> usize {
+        self.redo.size
+    }
+    /// Forgets every entry, without touching the target
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.transaction = None;
+        self.mergeable = false;
+    }
+
+    fn record(&mut self, group: Vec<C>) {
+        // Evicting the bottom never fails, the oldest entry is simply forgotten
+        let _ = self.undo.push(group);
+    }
+}
+
+impl<C: Command> Default for UndoHistory<C> {
+    fn default() -> Self {
+        UndoHistory::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Command, UndoHistory};
+
+    /// Inserts text at the end of a String, merging consecutive single characters
+    #[derive(Debug)]
+    struct Type(String);
+
+    impl Command for Type {
+        type Target = String;
+
+        fn apply(&mut self, target: &mut String) {
+            target.push_str(&self.0);
+        }
+
+        fn revert(&mut self, target: &mut String) {
+            target.truncate(target.len() - self.0.len());
+        }
+
+        fn merge(&mut self, next: Self) -> Result<(), Self> {
+            if next.0.len() == 1 && !next.0.starts_with(' ') {
+                self.0.push_str(&next.0);
+                Ok(())
+            } else {
+                Err(next)
+            }
+        }
+    }
+
+    fn typed(s: &str) -> Type {
+        Type(s.to_string())
+    }
+
+    #[test]
+    fn undo_redo() {
+        let mut text = String::new();
+        let mut history = UndoHistory::new();
+        history.execute(typed("ab"), &mut text);
+        history.seal();
+        history.execute(typed("cd"), &mut text);
+        assert_eq!(2, history.undo_len());
+        assert!(history.undo(&mut text));
+        assert_eq!("ab", text);
+        assert!(history.can_redo());
+        assert!(history.redo(&mut text));
+        assert_eq!("abcd", text);
+        assert!(history.undo(&mut text));
+        assert!(history.undo(&mut text));
+        assert!(!history.undo(&mut text));
+        assert_eq!("", text);
+
+        history.execute(typed("x"), &mut text);
+        assert!(!history.can_redo());
+        assert!(!history.redo(&mut text));
+        assert_eq!("x", text);
+    }
+
+    #[test]
+    fn coalescing() {
+        let mut text = String::new();
+        let mut history = UndoHistory::new();
+        for c in &["h", "i", " ", "y", "o"] {
+            history.execute(typed(c), &mut text);
+        }
+        assert_eq!("hi yo", text);
+        assert_eq!(2, history.undo_len());
+        history.undo(&mut text);
+        assert_eq!("hi", text);
+        history.undo(&mut text);
+        assert_eq!("", text);
+
+        // nothing merges into an entry that was just redone
+        history.redo(&mut text);
+        history.execute(typed("!"), &mut text);
+        assert_eq!(2, history.undo_len());
+    }
+
+    #[test]
+    fn transactions() {
+        let mut text = String::new();
+        let mut history = UndoHistory::new();
+        history.execute(typed("start "), &mut text);
+        history.begin();
+        history.execute(typed("one "), &mut text);
+        history.begin();
+        history.execute(typed("two "), &mut text);
+        assert!(history.commit());
+        assert!(!history.undo(&mut text));
+        history.execute(typed("three"), &mut text);
+        assert!(history.commit());
+        assert!(!history.commit());
+        assert_eq!(2, history.undo_len());
+        history.undo(&mut text);
+        assert_eq!("start ", text);
+        history.redo(&mut text);
+        assert_eq!("start one two three", text);
+    }
+
+    #[test]
+    fn depth_limit() {
+        let mut text = String::new();
+        let mut history = UndoHistory::with_limit(2);
+        for s in &["a ", "b ", "c "] {
+            history.execute(typed(s), &mut text);
+        }
+        assert_eq!(2, history.undo_len());
+        assert!(history.undo(&mut text));
+        assert!(history.undo(&mut text));
+        assert!(!history.undo(&mut text));
+        assert_eq!("a ", text);
+
+        let mut count = String::new();
+        let mut history = UndoHistory::with_limit(10_000);
+        for _ in 0..100_000 {
+            history.seal();
+            history.execute(typed("."), &mut count);
+        }
+        assert_eq!(10_000, history.undo_len());
+        while history.undo(&mut count) {}
+        assert_eq!(90_000, count.len());
+    }
+}
@@ -0,0 +1,197 @@
+//! Contiguous Stack Data Structure
+//!
+//! `VecStack` keeps its elements in a single `Vec` instead of one boxed tile per element,
+//! so pushes rarely allocate and the elements stay close together in memory
+//!
+//! It exposes the same `push`, `pop`, `peek`, `search` and `size` as `Stack`,
+//! so code can switch between the two without changes
+//!
+//! Example
+//! ```rust
+//! use stack::VecStack;
+//!
+//! let mut my_stack = VecStack::<i32>::with_capacity(8);
+//! my_stack.push(2);
+//! my_stack.push(4);
+//! assert_eq!(2, my_stack.size);
+//! assert_eq!(Some(&4), my_stack.peek());
+//! assert_eq!(Some(1), my_stack.search(2));
+//! ```
+
+use alloc::vec::{self, Vec};
+use core::iter::Rev;
+use core::slice;
+
+/// Stack Data Structure backed by contiguous memory
+/// It has a vec, holding the data from the bottom of the stack to the top
+/// It has a size, updated on every push and pop
+#[derive(Debug, Default)]
+pub struct VecStack<T> {
+    data: Vec<T>,
+    pub size: usize,
+}
+
+impl<T> VecStack<T> {
+    /// Initialize a new stack with zero size, without allocating
+    pub fn new() -> Self {
+        VecStack {
+            data: Vec::new(),
+            size: 0,
+        }
+    }
+    /// Initialize a new stack with zero size and room for `capacity` elements
+    ///
+    /// Example
+    /// ```rust
+    /// # use stack::VecStack;
+    /// let new_stack = VecStack::<i32>::with_capacity(64);
+    /// assert!(new_stack.capacity() >= 64);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        VecStack {
+            data: Vec::with_capacity(capacity),
+            size: 0,
+        }
+    }
+    /// Get a reference to data in the head of the stack
+    /// Returns None if the stack is empty
+    pub fn peek(&self) -> Option<&T> {
+        self.data.last()
+    }
+    /// Get a mutable reference to data in the head of the stack
+    /// Returns None if the stack is empty
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        self.data.last_mut()
+    }
+    /// Pops the top off the stack and returns the data it contains
+    /// Returns None if the stack is empty
+    ///
+    /// Reduces the size of stack by 1 unit
+    pub fn pop(&mut self) -> Option<T> {
+        let data = self.data.pop()?;
+        self.size -= 1;
+        Some(data)
+    }
+    /// Pushes the data onto the stack, growing the buffer if it is full
+    ///
+    /// Increases the size of stack by 1 unit
+    pub fn push(&mut self, data: T) {
+        self.data.push(data);
+        self.size += 1;
+    }
+    /// Removes all the data from the stack, keeping the allocated buffer
+    ///
+    /// Resets the size of stack to 0
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.size = 0;
+    }
+    /// Returns the number of elements the stack can hold without reallocating
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+    /// Reserves room for at least `additional` more elements
+    pub fn reserve(&mut self, additional: usize) {
+        self.data.reserve(additional);
+    }
+    /// Shrinks the buffer as close to the size of the stack as possible
+    pub fn shrink_to_fit(&mut self) {
+        self.data.shrink_to_fit();
+    }
+    /// Returns the data as a slice, from the bottom of the stack to the top
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+    /// Returns an iterator over references to the data, from the top of the stack to the bottom
+    pub fn iter(&self) -> Rev<slice::Iter<'_, T>> {
+        self.data.iter().rev()
+    }
+    /// Returns an iterator over mutable references to the data, from the top of the stack to the bottom
+    pub fn iter_mut(&mut self) -> Rev<slice::IterMut<'_, T>> {
+        self.data.iter_mut().rev()
+    }
+}
+
+impl<T: PartialEq> VecStack<T> {
+    /// Searches for data in the whole stack [O(n) operation]
+    /// Returns the position counted from the bottom of the stack, starting at 1
+    /// Returns None if the data is not found
+    pub fn search(&self, data: T) -> Option<usize> {
+        self.data
+            .iter()
+            .rposition(|value| *value == data)
+            .map(|i| i + 1)
+    }
+}
+
+impl<T> IntoIterator for VecStack<T> {
+    type Item = T;
+    type IntoIter = Rev<vec::IntoIter<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter().rev()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a VecStack<T> {
+    type Item = &'a T;
+    type IntoIter = Rev<slice::Iter<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut VecStack<T> {
+    type Item = &'a mut T;
+    type IntoIter = Rev<slice::IterMut<'a, T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VecStack;
+
+    #[test]
+    fn basics() {
+        let mut stack = VecStack::<u8>::new();
+        stack.push(3);
+        stack.push(6);
+        stack.push(9);
+        assert_eq!(3, stack.size);
+        assert_eq!(Some(&9), stack.peek());
+        assert_eq!(Some(9), stack.pop());
+        assert_eq!(Some(2), stack.search(6));
+        assert_eq!(2, stack.size);
+        assert_eq!(Some(6), stack.pop());
+        assert_eq!(Some(&3), stack.peek());
+        assert_eq!(&[3], stack.as_slice());
+    }
+
+    #[test]
+    fn capacity() {
+        let mut stack = VecStack::<u8>::with_capacity(4);
+        assert!(stack.capacity() >= 4);
+        stack.reserve(100);
+        assert!(stack.capacity() >= 100);
+        stack.push(1);
+        stack.shrink_to_fit();
+        assert!(stack.capacity() >= 1);
+        assert_eq!(vec![1], stack.into_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn iterators() {
+        let mut stack = VecStack::<u8>::new();
+        stack.push(1);
+        stack.push(2);
+        for data in &mut stack {
+            *data += 10;
+        }
+        assert_eq!(vec![&12, &11], stack.iter().collect::<Vec<_>>());
+        assert_eq!(vec![12, 11], stack.into_iter().collect::<Vec<_>>());
+    }
+}